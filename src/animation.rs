@@ -0,0 +1,136 @@
+use std::f64::consts::PI;
+
+use metaball::{Frame, RenderMode, Scene, Scene3d};
+
+const THRESHOLD_STEP: f64 = 0.1;
+const LIGHT_STEP: f64 = PI / 8.0;
+
+/// Keys selecting each [`RenderMode`] directly, in [`RenderMode::ALL`]
+/// order: the number row, then `0` and `!` past the ninth.
+const MODE_KEYS: &str = "1234567890!";
+
+/// What the main loop animates: the 2D [`Scene`] or the 3D [`Scene3d`].
+pub trait Animation {
+    fn resize(&mut self, width: usize, height: usize);
+
+    fn update(&mut self, dt: f64);
+
+    /// Step [`update`](Self::update) must be called with, if the scene
+    /// needs a particular one.
+    fn timestep(&self) -> Option<f64>;
+
+    fn render(&self) -> Frame;
+
+    /// Renders with blobs `alpha` of the way from their previous positions
+    /// to their current ones.
+    fn render_interpolated(&self, alpha: f64) -> Frame;
+
+    /// Applies a key the playback controls didn't use.
+    fn handle_key(&mut self, key: char);
+
+    /// The scene's part of the status line.
+    fn status(&self) -> String;
+}
+
+impl Animation for Scene {
+    fn resize(&mut self, width: usize, height: usize) {
+        Scene::resize(self, width, height);
+    }
+
+    fn update(&mut self, dt: f64) {
+        Scene::update(self, dt);
+    }
+
+    fn timestep(&self) -> Option<f64> {
+        Scene::timestep(self)
+    }
+
+    fn render(&self) -> Frame {
+        Scene::render(self)
+    }
+
+    fn render_interpolated(&self, alpha: f64) -> Frame {
+        self.interpolated(alpha).render()
+    }
+
+    fn handle_key(&mut self, key: char) {
+        match key {
+            'm' => self.set_mode(self.mode().next()),
+            'f' => self.falloff = self.falloff.next(),
+            'c' => self.coloring = self.coloring.next(),
+            'p' => self.palette = self.palette.next(),
+            's' => self.sampling = self.sampling.next(),
+            'g' => self.physics_enabled = !self.physics_enabled,
+            'n' => self.narrow_safe = !self.narrow_safe,
+            'l' => self.light.azimuth += LIGHT_STEP,
+            'L' => self.light.azimuth -= LIGHT_STEP,
+            '+' | '=' => self.threshold += THRESHOLD_STEP,
+            '-' | '_' => self.threshold = (self.threshold - THRESHOLD_STEP).max(THRESHOLD_STEP),
+            'a' => self.add_blob(),
+            'd' => self.remove_blob(),
+            _ => {
+                if let Some(&mode) = MODE_KEYS.find(key).and_then(|idx| RenderMode::ALL.get(idx)) {
+                    self.set_mode(mode);
+                }
+            }
+        }
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "Metaballs [{}] | Falloff: {} | Blobs: {} | Threshold: {:.1}{}",
+            self.mode().name(),
+            self.falloff.name(),
+            self.blobs.len(),
+            self.threshold,
+            if self.physics_enabled { " | Physics" } else { "" },
+        )
+    }
+}
+
+impl Animation for Scene3d {
+    fn resize(&mut self, width: usize, height: usize) {
+        Scene3d::resize(self, width, height);
+    }
+
+    fn update(&mut self, dt: f64) {
+        Scene3d::update(self, dt);
+    }
+
+    fn timestep(&self) -> Option<f64> {
+        None
+    }
+
+    fn render(&self) -> Frame {
+        Scene3d::render(self)
+    }
+
+    fn render_interpolated(&self, alpha: f64) -> Frame {
+        self.interpolated(alpha).render()
+    }
+
+    fn handle_key(&mut self, key: char) {
+        match key {
+            'f' => self.falloff = self.falloff.next(),
+            'c' => self.coloring = self.coloring.next(),
+            'p' => self.palette = self.palette.next(),
+            'n' => self.narrow_safe = !self.narrow_safe,
+            'l' => self.light.azimuth += LIGHT_STEP,
+            'L' => self.light.azimuth -= LIGHT_STEP,
+            '+' | '=' => self.threshold += THRESHOLD_STEP,
+            '-' | '_' => self.threshold = (self.threshold - THRESHOLD_STEP).max(THRESHOLD_STEP),
+            'a' => self.add_blob(),
+            'd' => self.remove_blob(),
+            _ => {}
+        }
+    }
+
+    fn status(&self) -> String {
+        format!(
+            "Metaballs [3D] | Falloff: {} | Blobs: {} | Threshold: {:.1}",
+            self.falloff.name(),
+            self.blobs.len(),
+            self.threshold,
+        )
+    }
+}
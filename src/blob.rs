@@ -0,0 +1,117 @@
+use crate::{ASPECT_RATIO, Falloff, FieldSource, Orbit, Rgb, Shape};
+
+/// A source of field strength: a [`Shape`] wrapped in a falloff kernel.
+#[derive(Clone, Debug)]
+pub struct Blob {
+    pub x: f64,
+    pub y: f64,
+    pub radius: f64,
+    pub shape: Shape,
+    /// `1.0` to add to the field, `-1.0` to carve away from it.
+    pub sign: f64,
+    /// Multiplier on the field, independent of how far it reaches.
+    pub strength: f64,
+    /// Colour used by the blob-based [`Coloring`](crate::Coloring)s; `None`
+    /// picks one from a built-in set by the blob's index.
+    pub color: Option<Rgb>,
+    /// Kernel for this blob; `None` uses the scene's.
+    pub falloff: Option<Falloff>,
+    /// Path the scene moves this blob along; `None` keeps it where it is.
+    /// Ignored while the scene runs [`Physics`](crate::Physics).
+    pub orbit: Option<Orbit>,
+    /// Horizontal velocity under physics, in columns per second.
+    pub vx: f64,
+    /// Vertical velocity under physics, in rows per second.
+    pub vy: f64,
+    /// Resistance to the forces between blobs.
+    pub mass: f64,
+    /// Heat from `0.0` to `1.0`, driving buoyancy and size in a
+    /// [`Lava`](crate::Lava) lamp.
+    pub temperature: f64,
+}
+
+impl Blob {
+    pub fn new(x: f64, y: f64, radius: f64) -> Self {
+        Self {
+            x,
+            y,
+            radius,
+            shape: Shape::Circle,
+            sign: 1.0,
+            strength: 1.0,
+            color: None,
+            falloff: None,
+            orbit: None,
+            vx: 0.0,
+            vy: 0.0,
+            mass: 1.0,
+            temperature: 0.0,
+        }
+    }
+
+    pub fn with_shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /// Makes this a subtractive blob that carves holes out of the surface.
+    pub fn negative(mut self) -> Self {
+        self.sign = -1.0;
+        self
+    }
+
+    pub fn with_strength(mut self, strength: f64) -> Self {
+        self.strength = strength;
+        self
+    }
+
+    pub fn with_color(mut self, color: Rgb) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_orbit(mut self, orbit: Orbit) -> Self {
+        self.orbit = Some(orbit);
+        self
+    }
+
+    pub fn with_velocity(mut self, vx: f64, vy: f64) -> Self {
+        self.vx = vx;
+        self.vy = vy;
+        self
+    }
+
+    pub fn with_mass(mut self, mass: f64) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Field contribution at `(px, py)`, with `x` scaled by [`ASPECT_RATIO`]
+    /// so blobs appear circular in the terminal.
+    pub fn field_at(&self, px: f64, py: f64) -> f64 {
+        self.field_with(px, py, Falloff::default())
+    }
+
+    /// Like [`field_at`](Self::field_at), using `default` if the blob has no
+    /// kernel of its own.
+    pub fn field_with(&self, px: f64, py: f64, default: Falloff) -> f64 {
+        let dx = (px - self.x) / ASPECT_RATIO;
+        let dy = py - self.y;
+        let dist_sq = self.shape.dist_sq(dx, dy);
+        let weight = self.sign * self.strength;
+        weight * self.falloff.unwrap_or(default).eval(dist_sq, self.radius)
+    }
+
+    /// Gradient of [`field_with`](Self::field_with) at `(px, py)`, per
+    /// column and per row.
+    pub fn gradient_with(&self, px: f64, py: f64, default: Falloff) -> (f64, f64) {
+        let dx = (px - self.x) / ASPECT_RATIO;
+        let dy = py - self.y;
+        let dist_sq = self.shape.dist_sq(dx, dy);
+        let (gx, gy) = self.shape.gradient(dx, dy);
+        let slope = self.falloff.unwrap_or(default).slope(dist_sq, self.radius);
+        let weight = self.sign * self.strength * slope;
+        // Chain rule through the aspect scaling of `dx`
+        (weight * gx / ASPECT_RATIO, weight * gy)
+    }
+}
@@ -0,0 +1,362 @@
+use std::path::PathBuf;
+
+use metaball::{
+    AmbiguousWidth, ColorDepth, Coloring, Falloff, LineStyle, MODE_CYCLE_SECONDS, PALETTES, Palette, RAMPS, Ramp,
+    Preset, RenderMode, Sampling,
+};
+
+use crate::{MAX_SPEED, MIN_SPEED};
+
+/// Slowest frame rate accepted, so the frame duration stays representable.
+const MIN_FPS: f64 = 0.1;
+
+/// Settings for a run of the demo, parsed from the command line.
+///
+/// Unset options fall back to the scene file, then to the built-in defaults.
+pub struct Options {
+    pub scene: Option<PathBuf>,
+    pub preset: Option<Preset>,
+    /// Ray-march 3D metaballs instead of the 2D scene.
+    pub three_d: bool,
+    pub mode: Option<RenderMode>,
+    pub falloff: Option<Falloff>,
+    pub coloring: Option<Coloring>,
+    pub palette: Option<Palette>,
+    pub line_style: Option<LineStyle>,
+    pub sampling: Option<Sampling>,
+    pub gradient_ramp: Option<Ramp>,
+    pub gooey_ramp: Option<Ramp>,
+    /// `None` detects the depth from the environment.
+    pub color_depth: Option<ColorDepth>,
+    /// `None` guesses from the locale.
+    pub ambiguous_width: Option<AmbiguousWidth>,
+    pub narrow_safe: bool,
+    /// Draw on the normal screen instead of the alternate one, leaving the
+    /// last frame in the scrollback.
+    pub no_alt_screen: bool,
+    pub no_cycle: bool,
+    pub cycle_seconds: Option<f64>,
+    /// Simulate the blobs instead of moving them along their orbits.
+    pub physics: bool,
+    /// Gravity for the simulation; setting it turns physics on.
+    pub gravity: Option<f64>,
+    pub fps: f64,
+    /// Simulated seconds per real second.
+    pub speed: f64,
+    /// Render blobs between simulation steps.
+    pub interpolate: bool,
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    pub blobs: Option<usize>,
+    pub threshold: Option<f64>,
+    pub seed: Option<u64>,
+    pub frames: Option<u64>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            scene: None,
+            preset: None,
+            three_d: false,
+            mode: None,
+            falloff: None,
+            coloring: None,
+            palette: None,
+            line_style: None,
+            sampling: None,
+            gradient_ramp: None,
+            gooey_ramp: None,
+            color_depth: None,
+            ambiguous_width: None,
+            narrow_safe: false,
+            no_alt_screen: false,
+            no_cycle: false,
+            cycle_seconds: None,
+            physics: false,
+            gravity: None,
+            fps: 30.0,
+            speed: 1.0,
+            interpolate: false,
+            width: None,
+            height: None,
+            blobs: None,
+            threshold: None,
+            seed: None,
+            frames: None,
+        }
+    }
+}
+
+pub enum Command {
+    Run(Box<Options>),
+    Help,
+}
+
+pub fn usage() -> String {
+    let modes: Vec<&str> = RenderMode::ALL.iter().map(|m| m.name()).collect();
+    let falloffs: Vec<&str> = Falloff::ALL.iter().map(|f| f.name()).collect();
+    let colorings: Vec<&str> = Coloring::ALL.iter().map(|c| c.name()).collect();
+    let line_styles: Vec<&str> = LineStyle::ALL.iter().map(|l| l.name()).collect();
+    let samplings: Vec<&str> = Sampling::ALL.iter().map(|s| s.name()).collect();
+    let presets: Vec<&str> = Preset::ALL.iter().map(|p| p.name()).collect();
+    format!(
+        "\
+ASCII metaball animation for the terminal.
+
+Usage: metaball [OPTIONS]
+
+Options:
+  --scene <PATH>         Load blobs and settings from a scene file
+  --preset <NAME>        Start from a built-in scene: {presets} [default: orbits]
+  --3d                   Ray-march metaballs in 3D (uses --ramp for glyphs)
+  --mode <MODE>          Start in MODE: {modes}
+  --falloff <KERNEL>     Field kernel: {falloffs}
+  --lines <STYLE>        Marching-squares glyphs: {line_styles} [default: Box]
+  --ramp <RAMP>          Gradient glyphs: {ramps}, level:glyph pairs
+                         (e.g. 0.5:.,1:o,2:O) or a string of glyphs [default: classic]
+  --gooey-ramp <RAMP>    Gooey glyphs, as --ramp [default: gooey]
+  --samples <N>          Samples per cell: {samplings} [default: 1]
+  --color <BY>           Colour cells by: {colorings} [default: Off]
+  --palette <PALETTE>    Field colours: {palettes}, or #rrggbb,#rrggbb,...
+  --color-depth <DEPTH>  auto, truecolor, 256, 16 or mono [default: auto]
+  --ambiguous-width <W>  auto, narrow or wide: how the terminal draws
+                         ambiguous glyphs like ○ and █ [default: auto]
+  --narrow-safe          Replace glyphs that might be drawn wide with ASCII
+  --no-alt-screen        Draw on the normal screen, leaving the last frame
+                         behind on exit
+  --no-cycle             Stay in one mode instead of cycling
+  --cycle-seconds <SECS> Seconds per mode when cycling [default: {MODE_CYCLE_SECONDS}]
+  --physics              Bounce blobs around under gravity instead of orbiting
+  --gravity <G>          Physics gravity in rows/s², negative pulls up
+                         (implies --physics) [default: 30]
+  --fps <FPS>            Target frames per second [default: 30]
+  --speed <X>            Simulation speed, {MIN_SPEED} to {MAX_SPEED} [default: 1]
+  --interpolate          Draw blobs between simulation steps for smoother
+                         motion at high frame rates
+  --width <COLS>         Scene width (default: fit the terminal)
+  --height <ROWS>        Scene height (default: fit the terminal)
+  --blobs <N>            Number of blobs [default: 5]
+  --threshold <VALUE>    Surface threshold [default: 1.0]
+  --seed <N>             Randomise blob sizes and orbits from N
+  --frames <N>           Exit after rendering N frames
+  -h, --help             Print this help",
+        modes = modes.join(", "),
+        falloffs = falloffs.join(", "),
+        colorings = colorings.join(", "),
+        palettes = PALETTES.join(", "),
+        line_styles = line_styles.join(", "),
+        samplings = samplings.join(", "),
+        presets = presets.join(", "),
+        ramps = RAMPS.join(", "),
+    )
+}
+
+/// Parses `args` (without the program name).
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut opts = Options::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        // Accept both `--flag value` and `--flag=value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} requires a value"))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--mode" => {
+                let name = value()?;
+                let mode = RenderMode::from_name(&name)
+                    .ok_or_else(|| format!("unknown mode '{name}'"))?;
+                opts.mode = Some(mode);
+            }
+            "--falloff" => {
+                let name = value()?;
+                let falloff = Falloff::from_name(&name)
+                    .ok_or_else(|| format!("unknown falloff '{name}'"))?;
+                opts.falloff = Some(falloff);
+            }
+            "--lines" => {
+                let name = value()?;
+                let style = LineStyle::from_name(&name)
+                    .ok_or_else(|| format!("unknown line style '{name}'"))?;
+                opts.line_style = Some(style);
+            }
+            "--ramp" => opts.gradient_ramp = Some(Ramp::parse(&value()?)?),
+            "--gooey-ramp" => opts.gooey_ramp = Some(Ramp::parse(&value()?)?),
+            "--samples" => {
+                let name = value()?;
+                let sampling = Sampling::from_name(&name)
+                    .ok_or_else(|| format!("unknown sampling '{name}'"))?;
+                opts.sampling = Some(sampling);
+            }
+            "--color" => {
+                let name = value()?;
+                let coloring = Coloring::from_name(&name)
+                    .ok_or_else(|| format!("unknown colouring '{name}'"))?;
+                opts.coloring = Some(coloring);
+            }
+            "--palette" => opts.palette = Some(Palette::parse(&value()?)?),
+            "--color-depth" => {
+                opts.color_depth = match value()?.to_ascii_lowercase().as_str() {
+                    "auto" => None,
+                    "truecolor" | "24bit" => Some(ColorDepth::TrueColor),
+                    "256" => Some(ColorDepth::Ansi256),
+                    "16" => Some(ColorDepth::Ansi16),
+                    "mono" | "none" => Some(ColorDepth::Mono),
+                    other => return Err(format!("unknown colour depth '{other}'")),
+                };
+            }
+            "--ambiguous-width" => {
+                let name = value()?;
+                opts.ambiguous_width = if name.eq_ignore_ascii_case("auto") {
+                    None
+                } else {
+                    let width = AmbiguousWidth::from_name(&name)
+                        .ok_or_else(|| format!("unknown ambiguous width '{name}'"))?;
+                    Some(width)
+                };
+            }
+            "--narrow-safe" => opts.narrow_safe = true,
+            "--no-alt-screen" => opts.no_alt_screen = true,
+            "--scene" => opts.scene = Some(PathBuf::from(value()?)),
+            "--preset" => {
+                let name = value()?;
+                opts.preset = Some(Preset::from_name(&name).ok_or_else(|| format!("unknown preset '{name}'"))?);
+            }
+            "--3d" => opts.three_d = true,
+            "--no-cycle" => opts.no_cycle = true,
+            "--cycle-seconds" => opts.cycle_seconds = Some(positive(&flag, &value()?)?),
+            "--physics" => opts.physics = true,
+            "--gravity" => opts.gravity = Some(finite(&flag, &value()?)?),
+            "--fps" => {
+                opts.fps = positive(&flag, &value()?)?;
+                if opts.fps < MIN_FPS {
+                    return Err(format!("--fps must be at least {MIN_FPS}"));
+                }
+            }
+            "--speed" => {
+                opts.speed = positive(&flag, &value()?)?;
+                if !(MIN_SPEED..=MAX_SPEED).contains(&opts.speed) {
+                    return Err(format!("--speed must be between {MIN_SPEED} and {MAX_SPEED}"));
+                }
+            }
+            "--interpolate" => opts.interpolate = true,
+            "--width" => opts.width = Some(number(&flag, &value()?)?),
+            "--height" => opts.height = Some(number(&flag, &value()?)?),
+            "--blobs" => opts.blobs = Some(number(&flag, &value()?)?),
+            "--threshold" => opts.threshold = Some(positive(&flag, &value()?)?),
+            "--seed" => opts.seed = Some(number(&flag, &value()?)?),
+            "--frames" => opts.frames = Some(number(&flag, &value()?)?),
+            _ => return Err(format!("unknown argument '{flag}'")),
+        }
+    }
+
+    if opts.scene.is_some() && opts.preset.is_some() {
+        return Err("--scene and --preset can't be used together".to_string());
+    }
+    if opts.width == Some(0) || opts.height == Some(0) {
+        return Err("--width and --height must be at least 1".to_string());
+    }
+    Ok(Command::Run(Box::new(opts)))
+}
+
+fn number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for {flag}"))
+}
+
+fn positive(flag: &str, value: &str) -> Result<f64, String> {
+    match number::<f64>(flag, value)? {
+        v if v > 0.0 && v.is_finite() => Ok(v),
+        _ => Err(format!("{flag} must be a positive number")),
+    }
+}
+
+fn finite(flag: &str, value: &str) -> Result<f64, String> {
+    match number::<f64>(flag, value)? {
+        v if v.is_finite() => Ok(v),
+        _ => Err(format!("{flag} must be a finite number")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn run(args: &[&str]) -> Options {
+        match parse_args(args) {
+            Ok(Command::Run(opts)) => *opts,
+            Ok(Command::Help) => panic!("{args:?} asked for help"),
+            Err(err) => panic!("{args:?} failed: {err}"),
+        }
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse_args(args) {
+            Err(err) => err,
+            Ok(_) => panic!("{args:?} parsed"),
+        }
+    }
+
+    #[test]
+    fn defaults_without_arguments() {
+        let opts = run(&[]);
+        assert_eq!(opts.fps, 30.0);
+        assert_eq!(opts.speed, 1.0);
+        assert!(opts.mode.is_none() && opts.scene.is_none() && !opts.three_d);
+        assert!(!opts.no_alt_screen);
+        assert!(run(&["--no-alt-screen"]).no_alt_screen);
+    }
+
+    #[test]
+    fn accepts_separate_and_inline_values() {
+        let opts = run(&["--fps", "60", "--mode=CONTOUR", "--blobs=8", "--seed", "42"]);
+        assert_eq!(opts.fps, 60.0);
+        assert_eq!(opts.mode, Some(RenderMode::Contour));
+        assert_eq!(opts.blobs, Some(8));
+        assert_eq!(opts.seed, Some(42));
+    }
+
+    #[test]
+    fn parses_named_options() {
+        let opts = run(&["--falloff", "compact", "--palette", "viridis", "--samples", "4x4", "--preset", "lava-lamp"]);
+        assert_eq!(opts.falloff, Some(Falloff::Compact));
+        assert_eq!(opts.palette.unwrap().name(), "viridis");
+        assert_eq!(opts.sampling, Some(Sampling::Grid4));
+        assert_eq!(opts.preset, Some(Preset::LavaLamp));
+        assert!(run(&["--ambiguous-width", "auto"]).ambiguous_width.is_none());
+    }
+
+    #[test]
+    fn help_wins() {
+        assert!(matches!(parse_args(&["--fps", "60", "-h"]), Ok(Command::Help)));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(error(&["--bogus"]), "unknown argument '--bogus'");
+        assert_eq!(error(&["--fps"]), "--fps requires a value");
+        assert_eq!(error(&["--fps", "fast"]), "invalid value 'fast' for --fps");
+        assert_eq!(error(&["--fps", "0"]), "--fps must be a positive number");
+        assert_eq!(error(&["--fps", "1e-320"]), "--fps must be at least 0.1");
+        assert_eq!(error(&["--speed", "0.05"]), "--speed must be between 0.1 and 10");
+        assert_eq!(error(&["--speed", "11"]), "--speed must be between 0.1 and 10");
+        assert_eq!(error(&["--width", "0"]), "--width and --height must be at least 1");
+        assert_eq!(error(&["--mode", "wavy"]), "unknown mode 'wavy'");
+        assert_eq!(error(&["--scene", "a.toml", "--preset", "orbits"]), "--scene and --preset can't be used together");
+    }
+}
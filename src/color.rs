@@ -0,0 +1,322 @@
+use std::env;
+use std::fmt::Write;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Rgb {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    /// Linear interpolation from `self` (at `t = 0`) to `other` (at `t = 1`).
+    pub fn lerp(self, other: Rgb, t: f64) -> Rgb {
+        let t = t.clamp(0.0, 1.0);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Rgb::new(mix(self.r, other.r), mix(self.g, other.g), mix(self.b, other.b))
+    }
+
+    /// Parses `#rrggbb` (the `#` is optional).
+    pub fn from_hex(hex: &str) -> Option<Rgb> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        Some(Rgb::new(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    /// Darkens the colour away from a light and washes it towards white in
+    /// the highlight, given [`Light::shade`](crate::Light::shade)'s terms.
+    pub(crate) fn lit(self, diffuse: f64, specular: f64) -> Rgb {
+        self.lerp(Rgb::new(0, 0, 0), 1.0 - diffuse.min(1.0))
+            .lerp(Rgb::new(255, 255, 255), specular)
+    }
+
+    fn dist_sq(self, other: Rgb) -> i32 {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+        d(self.r, other.r) + d(self.g, other.g) + d(self.b, other.b)
+    }
+}
+
+/// How many colours the terminal can display.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Ansi256,
+    Ansi16,
+    /// No colour escapes at all.
+    Mono,
+}
+
+/// The standard 16 ANSI colours, as xterm draws them.
+const ANSI16: [Rgb; 16] = [
+    Rgb::new(0, 0, 0),
+    Rgb::new(205, 0, 0),
+    Rgb::new(0, 205, 0),
+    Rgb::new(205, 205, 0),
+    Rgb::new(0, 0, 238),
+    Rgb::new(205, 0, 205),
+    Rgb::new(0, 205, 205),
+    Rgb::new(229, 229, 229),
+    Rgb::new(127, 127, 127),
+    Rgb::new(255, 0, 0),
+    Rgb::new(0, 255, 0),
+    Rgb::new(255, 255, 0),
+    Rgb::new(92, 92, 255),
+    Rgb::new(255, 0, 255),
+    Rgb::new(0, 255, 255),
+    Rgb::new(255, 255, 255),
+];
+
+/// Channel levels of the 6x6x6 cube in the 256-colour palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Picks the best depth the terminal advertises through `COLORTERM` and
+    /// `TERM`, honouring `NO_COLOR`.
+    pub fn detect() -> Self {
+        let var = |name| env::var(name).unwrap_or_default();
+        Self::from_env(&var("NO_COLOR"), &var("COLORTERM"), &var("TERM"))
+    }
+
+    /// The depth [`detect`](Self::detect) picks for these variable values,
+    /// with unset variables given as empty strings.
+    fn from_env(no_color: &str, colorterm: &str, term: &str) -> Self {
+        if !no_color.is_empty() {
+            return ColorDepth::Mono;
+        }
+        if colorterm.contains("truecolor") || colorterm.contains("24bit") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if term.is_empty() || term == "dumb" {
+            ColorDepth::Mono
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+
+    /// The colour the terminal will actually show for `color` at this depth,
+    /// so callers can skip escapes that wouldn't change anything.
+    pub fn quantize(self, color: Option<Rgb>) -> Option<Rgb> {
+        let c = color?;
+        match self {
+            ColorDepth::TrueColor => Some(c),
+            ColorDepth::Ansi256 => Some(ansi256_rgb(to_ansi256(c))),
+            ColorDepth::Ansi16 => Some(ANSI16[to_ansi16(c) as usize]),
+            ColorDepth::Mono => None,
+        }
+    }
+
+    /// Appends the SGR escape selecting `color` as the foreground (or
+    /// background), or the terminal default when `color` is `None`.
+    pub fn write_escape(self, out: &mut String, color: Option<Rgb>, background: bool) {
+        let base = if background { 40 } else { 30 };
+        let Some(c) = color else {
+            if self != ColorDepth::Mono {
+                let _ = write!(out, "\x1B[{}m", base + 9);
+            }
+            return;
+        };
+        let _ = match self {
+            ColorDepth::TrueColor => write!(out, "\x1B[{};2;{};{};{}m", base + 8, c.r, c.g, c.b),
+            ColorDepth::Ansi256 => write!(out, "\x1B[{};5;{}m", base + 8, to_ansi256(c)),
+            ColorDepth::Ansi16 => {
+                let idx = to_ansi16(c);
+                // Bright colours live at 90-97 / 100-107
+                let code = if idx < 8 { base + idx } else { base + 60 + idx - 8 };
+                write!(out, "\x1B[{code}m")
+            }
+            ColorDepth::Mono => Ok(()),
+        };
+    }
+}
+
+fn to_ansi16(c: Rgb) -> u8 {
+    (0..16u8)
+        .min_by_key(|&i| c.dist_sq(ANSI16[i as usize]))
+        .unwrap_or(0)
+}
+
+fn to_ansi256(c: Rgb) -> u8 {
+    let level = |v: u8| {
+        (0..6)
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(c.r), level(c.g), level(c.b));
+    let cube = Rgb::new(CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+
+    // The 24-step grey ramp is closer for near-neutral colours
+    let avg = (c.r as u32 + c.g as u32 + c.b as u32) / 3;
+    let grey_idx = ((avg.saturating_sub(8)) / 10).min(23) as u8;
+    let grey = 8 + grey_idx * 10;
+    if c.dist_sq(Rgb::new(grey, grey, grey)) < c.dist_sq(cube) {
+        232 + grey_idx
+    } else {
+        16 + 36 * r as u8 + 6 * g as u8 + b as u8
+    }
+}
+
+fn ansi256_rgb(idx: u8) -> Rgb {
+    match idx {
+        0..=15 => ANSI16[idx as usize],
+        16..=231 => {
+            let i = idx - 16;
+            let level = |n: u8| CUBE_LEVELS[n as usize];
+            Rgb::new(level(i / 36), level(i / 6 % 6), level(i % 6))
+        }
+        _ => {
+            let grey = 8 + (idx - 232) * 10;
+            Rgb::new(grey, grey, grey)
+        }
+    }
+}
+
+/// What a cell's colour is derived from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Coloring {
+    /// Monochrome output.
+    #[default]
+    Off,
+    /// The scene's [`Palette`](crate::Palette) over field strength.
+    Field,
+    /// Each cell takes the colour of the blob contributing most to it.
+    Blob,
+    /// Blob colours mixed in proportion to each blob's contribution, so
+    /// colours bleed into each other where blobs merge.
+    Blend,
+}
+
+/// Default blob colours, cycled by blob index.
+const BLOB_HUES: [Rgb; 8] = [
+    Rgb::new(243, 139, 168),
+    Rgb::new(137, 180, 250),
+    Rgb::new(166, 227, 161),
+    Rgb::new(249, 226, 175),
+    Rgb::new(203, 166, 247),
+    Rgb::new(148, 226, 213),
+    Rgb::new(250, 179, 135),
+    Rgb::new(245, 194, 231),
+];
+
+impl Coloring {
+    pub const ALL: [Coloring; 4] = [
+        Coloring::Off,
+        Coloring::Field,
+        Coloring::Blob,
+        Coloring::Blend,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            Coloring::Off => Coloring::Field,
+            Coloring::Field => Coloring::Blob,
+            Coloring::Blob => Coloring::Blend,
+            Coloring::Blend => Coloring::Off,
+        }
+    }
+
+    /// Looks up a colouring by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|coloring| coloring.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Coloring::Off => "Off",
+            Coloring::Field => "Field",
+            Coloring::Blob => "Blob",
+            Coloring::Blend => "Blend",
+        }
+    }
+}
+
+pub(crate) fn default_blob_color(index: usize) -> Rgb {
+    BLOB_HUES[index % BLOB_HUES.len()]
+}
+
+/// Index of the largest positive weight, for [`Coloring::Blob`].
+pub(crate) fn dominant(weights: impl IntoIterator<Item = f64>) -> Option<usize> {
+    weights
+        .into_iter()
+        .enumerate()
+        .filter(|&(_, weight)| weight > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(idx, _)| idx)
+}
+
+/// Colours averaged by their positive weights, for [`Coloring::Blend`], or
+/// `None` if no weight is positive.
+pub(crate) fn blend(weighted: impl IntoIterator<Item = (Rgb, f64)>) -> Option<Rgb> {
+    let (mut r, mut g, mut b, mut total) = (0.0, 0.0, 0.0, 0.0);
+    for (c, weight) in weighted {
+        if weight <= 0.0 {
+            continue;
+        }
+        r += weight * c.r as f64;
+        g += weight * c.g as f64;
+        b += weight * c.b as f64;
+        total += weight;
+    }
+    (total > 0.0).then(|| {
+        let channel = |sum: f64| (sum / total).round() as u8;
+        Rgb::new(channel(r), channel(g), channel(b))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_depth_from_the_environment() {
+        assert_eq!(ColorDepth::from_env("", "truecolor", "xterm"), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env("", "24bit", ""), ColorDepth::TrueColor);
+        assert_eq!(ColorDepth::from_env("", "", "xterm-256color"), ColorDepth::Ansi256);
+        assert_eq!(ColorDepth::from_env("", "", "xterm"), ColorDepth::Ansi16);
+        assert_eq!(ColorDepth::from_env("", "", "dumb"), ColorDepth::Mono);
+        assert_eq!(ColorDepth::from_env("", "", ""), ColorDepth::Mono);
+        assert_eq!(ColorDepth::from_env("1", "truecolor", "xterm-256color"), ColorDepth::Mono);
+    }
+
+    #[test]
+    fn quantizes_to_the_nearest_cube_level() {
+        // Cube levels 0 and 95 meet at 47.5, 95 and 135 at 115
+        assert_eq!(to_ansi256(Rgb::new(47, 0, 255)), 16 + 5);
+        assert_eq!(to_ansi256(Rgb::new(48, 0, 255)), 16 + 36 + 5);
+        assert_eq!(to_ansi256(Rgb::new(255, 114, 0)), 16 + 5 * 36 + 6);
+        assert_eq!(to_ansi256(Rgb::new(255, 116, 0)), 16 + 5 * 36 + 2 * 6);
+        assert_eq!(to_ansi256(Rgb::new(0, 0, 0)), 16);
+        assert_eq!(to_ansi256(Rgb::new(255, 255, 255)), 231);
+        assert_eq!(
+            ColorDepth::Ansi256.quantize(Some(Rgb::new(100, 0, 0))),
+            Some(Rgb::new(95, 0, 0))
+        );
+    }
+
+    #[test]
+    fn quantizes_greys_to_the_grey_ramp() {
+        assert_eq!(to_ansi256(Rgb::new(128, 128, 128)), 244);
+        assert_eq!(ansi256_rgb(244), Rgb::new(128, 128, 128));
+        assert_eq!(to_ansi256(Rgb::new(20, 18, 19)), 233);
+    }
+
+    #[test]
+    fn quantizes_to_the_nearest_ansi16_colour() {
+        // Red and bright red are 205 and 255; the tie at 230 goes to red
+        assert_eq!(to_ansi16(Rgb::new(229, 0, 0)), 1);
+        assert_eq!(to_ansi16(Rgb::new(231, 0, 0)), 9);
+        let mut out = String::new();
+        ColorDepth::Ansi16.write_escape(&mut out, Some(Rgb::new(255, 0, 0)), false);
+        ColorDepth::Ansi16.write_escape(&mut out, Some(Rgb::new(205, 0, 0)), true);
+        assert_eq!(out, "\x1B[91m\x1B[41m");
+    }
+}
@@ -0,0 +1,219 @@
+/// Finite-support kernels reach zero at this multiple of the blob radius.
+pub const SUPPORT_SCALE: f64 = 2.0;
+
+/// Peak of the Gaussian kernel is `e^BLOBBINESS` times its value at the radius.
+const BLOBBINESS: f64 = 2.0;
+
+/// How a blob's field falls off with distance.
+///
+/// Every kernel is scaled to equal `1.0` at the blob's radius, so a lone blob
+/// has the same size at the default threshold whichever kernel it uses; they
+/// differ in how sharply they peak and how far their influence reaches.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Falloff {
+    /// `r² / d²`: infinite tail, singular at the centre.
+    #[default]
+    InverseSquare,
+    /// Blinn's exponential: `e^(b(1 - d²/r²))`.
+    Gaussian,
+    /// Wyvill's soft-object polynomial, zero beyond [`SUPPORT_SCALE`] radii.
+    SoftObject,
+    /// Nishimura's piecewise quadratic, zero beyond [`SUPPORT_SCALE`] radii.
+    Nishimura,
+    /// `(1 - d²/R²)²`, zero beyond [`SUPPORT_SCALE`] radii.
+    Compact,
+}
+
+impl Falloff {
+    pub const ALL: [Falloff; 5] = [
+        Falloff::InverseSquare,
+        Falloff::Gaussian,
+        Falloff::SoftObject,
+        Falloff::Nishimura,
+        Falloff::Compact,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            Falloff::InverseSquare => Falloff::Gaussian,
+            Falloff::Gaussian => Falloff::SoftObject,
+            Falloff::SoftObject => Falloff::Nishimura,
+            Falloff::Nishimura => Falloff::Compact,
+            Falloff::Compact => Falloff::InverseSquare,
+        }
+    }
+
+    /// Looks up a kernel by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|falloff| falloff.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Falloff::InverseSquare => "InverseSquare",
+            Falloff::Gaussian => "Gaussian",
+            Falloff::SoftObject => "SoftObject",
+            Falloff::Nishimura => "Nishimura",
+            Falloff::Compact => "Compact",
+        }
+    }
+
+    /// Distance beyond which a blob of `radius` contributes nothing, or
+    /// `None` for kernels with an infinite tail.
+    pub fn support(self, radius: f64) -> Option<f64> {
+        match self {
+            Falloff::InverseSquare | Falloff::Gaussian => None,
+            Falloff::SoftObject | Falloff::Nishimura | Falloff::Compact => {
+                Some(radius * SUPPORT_SCALE)
+            }
+        }
+    }
+
+    /// Distance from a blob of `radius` beyond which its field stays below
+    /// `level`.
+    pub fn reach(self, radius: f64, level: f64) -> f64 {
+        match self {
+            Falloff::InverseSquare => radius / level.sqrt(),
+            // Solves e^(b(1 - d²/r²)) = level for d
+            Falloff::Gaussian => radius * (1.0 - level.ln() / BLOBBINESS).max(0.0).sqrt(),
+            Falloff::SoftObject | Falloff::Nishimura | Falloff::Compact => radius * SUPPORT_SCALE,
+        }
+    }
+
+    /// Field strength at squared distance `dist_sq` from a blob of `radius`.
+    pub fn eval(self, dist_sq: f64, radius: f64) -> f64 {
+        let r_sq = radius * radius;
+        if let Some(support) = self.support(radius)
+            && dist_sq >= support * support
+        {
+            return 0.0;
+        }
+
+        match self {
+            Falloff::InverseSquare => {
+                if dist_sq < 0.0001 {
+                    return 1000.0;
+                }
+                r_sq / dist_sq
+            }
+            Falloff::Gaussian => (BLOBBINESS * (1.0 - dist_sq / r_sq)).exp(),
+            Falloff::SoftObject => {
+                // C(s) = 1 - 4/9 s³ + 17/9 s² - 22/9 s, which is 1/2 at d = R/2
+                let s = dist_sq / (r_sq * SUPPORT_SCALE * SUPPORT_SCALE);
+                let c = 1.0 - (4.0 / 9.0) * s * s * s + (17.0 / 9.0) * s * s - (22.0 / 9.0) * s;
+                2.0 * c
+            }
+            Falloff::Nishimura => {
+                // b(1 - 3d²/R²) inside R/3, (3b/2)(1 - d/R)² out to R, with b
+                // chosen so the field is 1 at d = R/2
+                let big_r = radius * SUPPORT_SCALE;
+                let b = 8.0 / 3.0;
+                let d = dist_sq.sqrt();
+                if d <= big_r / 3.0 {
+                    b * (1.0 - 3.0 * dist_sq / (big_r * big_r))
+                } else {
+                    1.5 * b * (1.0 - d / big_r).powi(2)
+                }
+            }
+            Falloff::Compact => {
+                let t = 1.0 - dist_sq / (r_sq * SUPPORT_SCALE * SUPPORT_SCALE);
+                // (1 - 1/4)² = 9/16 at d = R/2
+                (16.0 / 9.0) * t * t
+            }
+        }
+    }
+
+    /// Rate of change of [`eval`](Self::eval) with `dist_sq`, for analytic
+    /// field gradients.
+    pub fn slope(self, dist_sq: f64, radius: f64) -> f64 {
+        let r_sq = radius * radius;
+        if let Some(support) = self.support(radius)
+            && dist_sq >= support * support
+        {
+            return 0.0;
+        }
+
+        match self {
+            Falloff::InverseSquare => {
+                // Flat inside the clamp `eval` applies at the centre
+                if dist_sq < 0.0001 {
+                    return 0.0;
+                }
+                -r_sq / (dist_sq * dist_sq)
+            }
+            Falloff::Gaussian => -BLOBBINESS / r_sq * self.eval(dist_sq, radius),
+            Falloff::SoftObject => {
+                let scale = r_sq * SUPPORT_SCALE * SUPPORT_SCALE;
+                let s = dist_sq / scale;
+                let dc = -(4.0 / 3.0) * s * s + (34.0 / 9.0) * s - 22.0 / 9.0;
+                2.0 * dc / scale
+            }
+            Falloff::Nishimura => {
+                let big_r = radius * SUPPORT_SCALE;
+                let b = 8.0 / 3.0;
+                let d = dist_sq.sqrt();
+                if d <= big_r / 3.0 {
+                    -3.0 * b / (big_r * big_r)
+                } else {
+                    // d/d(d²) = d/dd / 2d
+                    -3.0 * b * (1.0 - d / big_r) / big_r / (2.0 * d)
+                }
+            }
+            Falloff::Compact => {
+                let scale = r_sq * SUPPORT_SCALE * SUPPORT_SCALE;
+                let t = 1.0 - dist_sq / scale;
+                -(32.0 / 9.0) * t / scale
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_kernel_is_one_at_the_radius() {
+        for falloff in Falloff::ALL {
+            for radius in [0.5, 1.0, 3.0] {
+                let field = falloff.eval(radius * radius, radius);
+                assert!((field - 1.0).abs() < 1e-12, "{} gave {field} at radius {radius}", falloff.name());
+            }
+        }
+    }
+
+    #[test]
+    fn finite_kernels_vanish_at_their_support() {
+        for falloff in Falloff::ALL {
+            if let Some(support) = falloff.support(2.0) {
+                assert_eq!(falloff.eval(support * support, 2.0), 0.0, "{}", falloff.name());
+                assert!(falloff.eval(support * support * 0.99, 2.0) > 0.0, "{}", falloff.name());
+            }
+        }
+    }
+
+    #[test]
+    fn field_falls_below_level_beyond_reach() {
+        for falloff in Falloff::ALL {
+            for level in [0.05, 0.5, 1.0, 4.0] {
+                let reach = falloff.reach(2.0, level);
+                let field = falloff.eval(reach * reach * 1.0001, 2.0);
+                assert!(field < level, "{} gave {field} beyond reach for {level}", falloff.name());
+            }
+        }
+    }
+
+    #[test]
+    fn slope_matches_eval() {
+        let h = 1e-6;
+        for falloff in Falloff::ALL {
+            for dist_sq in [0.5, 2.0, 6.0, 12.0] {
+                let numeric = (falloff.eval(dist_sq + h, 2.0) - falloff.eval(dist_sq - h, 2.0)) / (2.0 * h);
+                let analytic = falloff.slope(dist_sq, 2.0);
+                assert!((numeric - analytic).abs() < 1e-5, "{} at {dist_sq}: {numeric} vs {analytic}", falloff.name());
+            }
+        }
+    }
+}
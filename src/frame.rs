@@ -0,0 +1,253 @@
+use std::fmt;
+
+use crate::{AmbiguousWidth, ColorDepth, Rgb, char_width, is_narrow_safe, narrow_fallback};
+
+/// One character cell, with optional foreground and background colours.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub fg: Option<Rgb>,
+    pub bg: Option<Rgb>,
+}
+
+impl Cell {
+    pub const BLANK: Cell = Cell::new(' ');
+
+    /// The right half of a wide glyph in the cell to its left; nothing is
+    /// written for it.
+    pub const COVERED: Cell = Cell::new('\0');
+
+    pub const fn new(ch: char) -> Self {
+        Self {
+            ch,
+            fg: None,
+            bg: None,
+        }
+    }
+}
+
+impl From<char> for Cell {
+    fn from(ch: char) -> Self {
+        Cell::new(ch)
+    }
+}
+
+/// A rendered grid of cells, stored row-major.
+#[derive(Clone, Debug)]
+pub struct Frame {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::BLANK; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, col: usize, row: usize) -> Cell {
+        self.cells[row * self.width + col]
+    }
+
+    pub fn set(&mut self, col: usize, row: usize, cell: impl Into<Cell>) {
+        self.cells[row * self.width + col] = cell.into();
+    }
+
+    /// Makes every row exactly `width` columns on a terminal that draws
+    /// ambiguous characters `ambiguous`. Glyphs with no width become spaces
+    /// and wide glyphs cover the cell to their right, unless they're in the
+    /// last column, where they're swapped for a [`narrow_fallback`]. With
+    /// `narrow_safe`, every glyph that could be wide is swapped instead.
+    pub fn fit_widths(&mut self, ambiguous: AmbiguousWidth, narrow_safe: bool) {
+        for row in self.cells.chunks_mut(self.width.max(1)) {
+            let mut col = 0;
+            while col < row.len() {
+                let last = col + 1 == row.len();
+                let cell = &mut row[col];
+                if narrow_safe && !is_narrow_safe(cell.ch) {
+                    cell.ch = narrow_fallback(cell.ch);
+                }
+                match char_width(cell.ch, ambiguous) {
+                    0 => cell.ch = ' ',
+                    2 if last => cell.ch = narrow_fallback(cell.ch),
+                    2 => {
+                        col += 1;
+                        row[col] = Cell::COVERED;
+                    }
+                    _ => {}
+                }
+                col += 1;
+            }
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The frame as text with ANSI colour escapes for `depth`, each row
+    /// followed by a newline.
+    ///
+    /// Escapes are only emitted where the colour the terminal shows changes
+    /// from the previous cell, and colours are reset at the end so following
+    /// output is plain.
+    pub fn to_ansi(&self, depth: ColorDepth) -> String {
+        let mut out = String::with_capacity(self.width * self.height * 4);
+        let (mut fg, mut bg) = (None, None);
+        for row in self.rows() {
+            for cell in row.iter().filter(|cell| **cell != Cell::COVERED) {
+                let (cell_fg, cell_bg) = (depth.quantize(cell.fg), depth.quantize(cell.bg));
+                if cell_fg != fg {
+                    depth.write_escape(&mut out, cell_fg, false);
+                    fg = cell_fg;
+                }
+                if cell_bg != bg {
+                    depth.write_escape(&mut out, cell_bg, true);
+                    bg = cell_bg;
+                }
+                out.push(cell.ch);
+            }
+            // Don't let a background colour bleed past the row when the
+            // terminal scrolls or clears
+            if bg.is_some() {
+                depth.write_escape(&mut out, None, true);
+                bg = None;
+            }
+            out.push('\n');
+        }
+        if fg.is_some() {
+            depth.write_escape(&mut out, None, false);
+        }
+        out
+    }
+}
+
+impl fmt::Display for Frame {
+    /// Writes each row's characters, without colour, followed by a newline.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut line = String::with_capacity(self.width * 4 + 1);
+        for row in self.rows() {
+            line.clear();
+            line.extend(
+                row.iter()
+                    .filter(|cell| **cell != Cell::COVERED)
+                    .map(|cell| cell.ch),
+            );
+            line.push('\n');
+            f.write_str(&line)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROWS: [&str; 4] = ["字字字字字", "○█○█○", "a\u{301}\u{200B}bc", "字○\u{200B}字x"];
+
+    /// A frame with one row per string, each character in its own cell.
+    fn frame(rows: &[&str]) -> Frame {
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let mut frame = Frame::new(width, rows.len());
+        for (row, text) in rows.iter().enumerate() {
+            for (col, ch) in text.chars().enumerate() {
+                frame.set(col, row, ch);
+            }
+        }
+        frame
+    }
+
+    /// Columns each printed row takes on a terminal drawing ambiguous
+    /// glyphs `ambiguous`.
+    fn columns(frame: &Frame, ambiguous: AmbiguousWidth) -> Vec<usize> {
+        frame
+            .to_string()
+            .lines()
+            .map(|line| line.chars().map(|ch| char_width(ch, ambiguous)).sum())
+            .collect()
+    }
+
+    /// A one-row frame of `text` with the foreground `colors`.
+    fn colored(text: &str, colors: &[Option<Rgb>]) -> Frame {
+        let mut frame = Frame::new(colors.len(), 1);
+        for (col, (ch, &fg)) in text.chars().zip(colors).enumerate() {
+            frame.set(col, 0, Cell { fg, ..Cell::new(ch) });
+        }
+        frame
+    }
+
+    #[test]
+    fn escapes_only_colour_changes() {
+        let (red, blue) = (Some(Rgb::new(255, 0, 0)), Some(Rgb::new(0, 0, 255)));
+        let frame = colored("abcde", &[red, red, blue, None, red]);
+        assert_eq!(
+            frame.to_ansi(ColorDepth::TrueColor),
+            "\x1B[38;2;255;0;0mab\x1B[38;2;0;0;255mc\x1B[39md\x1B[38;2;255;0;0me\n\x1B[39m"
+        );
+        assert_eq!(frame.to_ansi(ColorDepth::Mono), "abcde\n");
+    }
+
+    #[test]
+    fn escapes_once_for_colours_that_quantize_alike() {
+        let frame = colored("abc", &[Some(Rgb::new(250, 0, 0)), Some(Rgb::new(240, 10, 0)), None]);
+        assert_eq!(frame.to_ansi(ColorDepth::Ansi16), "\x1B[91mab\x1B[39mc\n");
+    }
+
+    #[test]
+    fn resets_the_background_at_the_end_of_each_row() {
+        let mut frame = Frame::new(1, 2);
+        let bg = Some(Rgb::new(0, 0, 0));
+        frame.set(0, 0, Cell { bg, ..Cell::new('a') });
+        frame.set(0, 1, Cell { bg, ..Cell::new('b') });
+        assert_eq!(
+            frame.to_ansi(ColorDepth::Ansi256),
+            "\x1B[48;5;16ma\x1B[49m\n\x1B[48;5;16mb\x1B[49m\n"
+        );
+    }
+
+    #[test]
+    fn every_row_fills_the_width() {
+        for ambiguous in AmbiguousWidth::ALL {
+            for narrow_safe in [false, true] {
+                let mut frame = frame(&ROWS);
+                frame.fit_widths(ambiguous, narrow_safe);
+                assert_eq!(
+                    columns(&frame, ambiguous),
+                    vec![5; ROWS.len()],
+                    "{} ambiguous, narrow_safe {narrow_safe}",
+                    ambiguous.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wide_glyphs_cover_their_neighbour() {
+        let mut frame = frame(&["字ab字"]);
+        frame.fit_widths(AmbiguousWidth::Narrow, false);
+        assert_eq!(frame.get(1, 0), Cell::COVERED);
+        // No room for the second half in the last column
+        assert_eq!(frame.get(3, 0).ch, narrow_fallback('字'));
+        assert_eq!(frame.to_string(), "字b#\n");
+    }
+
+    #[test]
+    fn narrow_safe_leaves_only_narrow_glyphs() {
+        let mut frame = frame(&ROWS);
+        frame.fit_widths(AmbiguousWidth::Narrow, true);
+        assert!(frame.rows().flatten().all(|cell| is_narrow_safe(cell.ch)));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{ASPECT_RATIO, Blob};
+
+/// Temperature-driven behaviour layered on [`Physics`](crate::Physics) to
+/// make a lava lamp.
+///
+/// Blobs heat up near the bottom of the viewport and cool everywhere else.
+/// Hot wax is lighter than the surrounding liquid and swells, so blobs rise
+/// from the bottom, cool, shrink and sink back. Large hot blobs split in two
+/// and cool blobs that run into each other merge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Lava {
+    /// Upward acceleration at full temperature, in rows per second squared;
+    /// wax floats where this outweighs gravity.
+    pub buoyancy: f64,
+    /// Temperature gained per second inside the heated zone by a blob of
+    /// radius 1; larger blobs heat proportionally slower.
+    pub heating: f64,
+    /// Temperature lost per second outside it, scaled like `heating`.
+    pub cooling: f64,
+    /// Height of the heated zone at the bottom, as a fraction of the viewport.
+    pub heat_zone: f64,
+    /// Fractional growth of a blob's radius at full temperature.
+    pub expansion: f64,
+    /// Radius (at zero temperature) above which a hot blob splits in two.
+    pub split_radius: f64,
+    /// Temperature at or above which large blobs split.
+    pub split_temperature: f64,
+    /// Temperature below which touching blobs merge.
+    pub merge_temperature: f64,
+}
+
+impl Default for Lava {
+    fn default() -> Self {
+        Self {
+            buoyancy: 12.0,
+            heating: 1.5,
+            cooling: 0.25,
+            heat_zone: 0.2,
+            expansion: 0.3,
+            split_radius: 3.5,
+            split_temperature: 0.9,
+            merge_temperature: 0.4,
+        }
+    }
+}
+
+impl Lava {
+    /// Advances temperatures by `dt` seconds in a `width` x `height`
+    /// viewport, resizing, pushing, splitting and merging `blobs` to match.
+    /// Run before each physics step. Returns whether any blobs split or
+    /// merged, moving others to new indices.
+    pub fn step(&self, blobs: &mut Vec<Blob>, width: f64, height: f64, dt: f64) -> bool {
+        let heated_below = height * (1.0 - self.heat_zone);
+        for blob in blobs.iter_mut() {
+            let base = blob.radius / self.swell(blob.temperature);
+            let rate = if blob.y >= heated_below { self.heating } else { -self.cooling };
+            blob.temperature = (blob.temperature + rate / base * dt).clamp(0.0, 1.0);
+            blob.radius = base * self.swell(blob.temperature);
+            blob.vy -= self.buoyancy * blob.temperature * dt;
+        }
+        let split = self.split(blobs, width);
+        let merged = self.merge(blobs);
+        split || merged
+    }
+
+    /// How much a blob's radius is scaled at `temperature`.
+    fn swell(&self, temperature: f64) -> f64 {
+        1.0 + self.expansion * temperature
+    }
+
+    /// Splits each large hot blob into two of half the area, side by side,
+    /// returning whether any split.
+    fn split(&self, blobs: &mut Vec<Blob>, width: f64) -> bool {
+        let count = blobs.len();
+        for i in 0..count {
+            let blob = &mut blobs[i];
+            let base = blob.radius / self.swell(blob.temperature);
+            if base <= self.split_radius || blob.temperature < self.split_temperature {
+                continue;
+            }
+            blob.radius /= 2f64.sqrt();
+            blob.mass /= 2.0;
+            let offset = (blob.radius * ASPECT_RATIO).min(width / 4.0);
+            // Nudge the halves apart so they don't stick back together
+            let kick = blob.radius * ASPECT_RATIO;
+            let mut half = blob.clone();
+            blob.x -= offset;
+            blob.vx -= kick;
+            half.x += offset;
+            half.vx += kick;
+            blobs.push(half);
+        }
+        blobs.len() != count
+    }
+
+    /// Merges pairs of cool blobs whose cores overlap into one of their
+    /// combined area and momentum, returning whether any merged.
+    fn merge(&self, blobs: &mut Vec<Blob>) -> bool {
+        let count = blobs.len();
+        let mut i = 0;
+        while i < blobs.len() {
+            let mut j = i + 1;
+            while j < blobs.len() {
+                let (a, b) = (&blobs[i], &blobs[j]);
+                let dx = (b.x - a.x) / ASPECT_RATIO;
+                let dy = b.y - a.y;
+                let cool = a.temperature.max(b.temperature) < self.merge_temperature;
+                if !cool || (dx * dx + dy * dy).sqrt() >= (a.radius + b.radius) / 2.0 {
+                    j += 1;
+                    continue;
+                }
+                let b = blobs.remove(j);
+                let a = &mut blobs[i];
+                let mass = a.mass + b.mass;
+                let mix = |p: f64, q: f64| (p * a.mass + q * b.mass) / mass;
+                let (x, y) = (mix(a.x, b.x), mix(a.y, b.y));
+                let (vx, vy) = (mix(a.vx, b.vx), mix(a.vy, b.vy));
+                let temperature = mix(a.temperature, b.temperature);
+                if b.radius > a.radius {
+                    a.color = b.color;
+                }
+                let base = (a.radius / self.swell(a.temperature)).hypot(b.radius / self.swell(b.temperature));
+                (a.x, a.y, a.vx, a.vy, a.mass) = (x, y, vx, vy, mass);
+                a.temperature = temperature;
+                a.radius = base * self.swell(temperature);
+                // The merged blob may now reach others, so check it again
+                j = i + 1;
+            }
+            i += 1;
+        }
+        blobs.len() != count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(x: f64, y: f64, radius: f64, temperature: f64) -> Blob {
+        let mut blob = Blob::new(x, y, radius);
+        blob.temperature = temperature;
+        blob
+    }
+
+    /// Total area (as radius squared at zero temperature) and momentum.
+    fn totals(lava: &Lava, blobs: &[Blob]) -> (f64, f64, f64) {
+        blobs.iter().fold((0.0, 0.0, 0.0), |(area, px, py), b| {
+            let base = b.radius / lava.swell(b.temperature);
+            (area + base * base, px + b.mass * b.vx, py + b.mass * b.vy)
+        })
+    }
+
+    fn assert_close(a: (f64, f64, f64), b: (f64, f64, f64)) {
+        assert!((a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9 && (a.2 - b.2).abs() < 1e-9, "{a:?} != {b:?}");
+    }
+
+    #[test]
+    fn splitting_conserves_area_and_momentum() {
+        let lava = Lava::default();
+        let mut hot = blob(40.0, 18.0, 4.0 * lava.swell(1.0), 1.0);
+        (hot.vx, hot.vy, hot.mass) = (3.0, -1.0, 2.0);
+        let mut blobs = vec![hot];
+        let before = totals(&lava, &blobs);
+        assert!(lava.split(&mut blobs, 80.0));
+        assert_eq!(blobs.len(), 2);
+        assert_close(totals(&lava, &blobs), before);
+    }
+
+    #[test]
+    fn merging_conserves_area_and_momentum() {
+        let lava = Lava::default();
+        let (mut a, mut b) = (blob(40.0, 5.0, 2.0, 0.1), blob(41.0, 5.5, 3.0, 0.2));
+        (a.vx, a.vy) = (2.0, 1.0);
+        (b.vx, b.vy, b.mass) = (-1.0, 4.0, 3.0);
+        let mut blobs = vec![a, b];
+        let before = totals(&lava, &blobs);
+        assert!(lava.merge(&mut blobs));
+        assert_eq!(blobs.len(), 1);
+        assert_close(totals(&lava, &blobs), before);
+    }
+
+    #[test]
+    fn step_reports_changes_to_the_blobs() {
+        let lava = Lava::default();
+        let step = |mut blobs: Vec<Blob>| {
+            let count = blobs.len();
+            let changed = lava.step(&mut blobs, 80.0, 20.0, 0.01);
+            (changed, blobs.len() != count)
+        };
+        // Apart and warm: nothing happens
+        assert_eq!(step(vec![blob(20.0, 5.0, 1.0, 0.5), blob(60.0, 5.0, 1.0, 0.5)]), (false, false));
+        // Big and hot in the heated zone: splits
+        assert_eq!(step(vec![blob(40.0, 18.0, 4.0 * lava.swell(1.0), 1.0)]), (true, true));
+        // Cool and overlapping: merge
+        assert_eq!(step(vec![blob(40.0, 5.0, 2.0, 0.0), blob(40.5, 5.0, 2.0, 0.0)]), (true, true));
+    }
+}
@@ -0,0 +1,52 @@
+//! ASCII metaball rendering.
+//!
+//! A [`Scene`] holds a set of [`Blob`]s whose scalar fields are summed and
+//! rendered against [`THRESHOLD`] into a [`Frame`] of characters using one of
+//! the [`RenderMode`]s, optionally coloured according to its [`Coloring`].
+
+mod blob;
+mod color;
+mod falloff;
+mod frame;
+mod lava;
+mod light;
+mod orbit;
+mod palette;
+mod physics;
+mod ramp;
+mod render;
+mod rng;
+mod scene;
+mod scene3d;
+mod scene_file;
+mod shape;
+mod width;
+
+pub use blob::Blob;
+pub use color::{ColorDepth, Coloring, Rgb};
+pub use falloff::{Falloff, SUPPORT_SCALE};
+pub use frame::{Cell, Frame};
+pub use lava::Lava;
+pub use light::Light;
+pub use orbit::Orbit;
+pub use palette::{PALETTES, Palette};
+pub use physics::Physics;
+pub use ramp::{RAMPS, Ramp};
+pub use render::{LineStyle, RenderMode, Sampling};
+pub use scene::{MODE_CYCLE_SECONDS, Preset, Scene};
+pub use scene3d::{Blob3d, Camera, Orbit3d, Scene3d, Vec3};
+pub use scene_file::{SceneFile, SceneFileError};
+pub use shape::{Capsule, Ellipse, FieldSource, RoundedRect, Shape};
+pub use width::{AmbiguousWidth, char_width, is_narrow_safe, narrow_fallback};
+
+/// Default scene width in character cells.
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Default scene height in character cells.
+pub const DEFAULT_HEIGHT: usize = 35;
+
+/// Field value at which a point is considered inside the surface.
+pub const THRESHOLD: f64 = 1.0;
+
+/// Terminal character cells are roughly twice as tall as they are wide.
+pub const ASPECT_RATIO: f64 = 2.0;
@@ -0,0 +1,59 @@
+use std::f64::consts::PI;
+
+/// Ambient light reaching every surface.
+const AMBIENT: f64 = 0.1;
+
+/// Weight of the Lambert diffuse term.
+const DIFFUSE: f64 = 0.75;
+
+/// Weight of the Blinn-Phong specular highlight.
+const SPECULAR: f64 = 0.6;
+
+/// Blinn-Phong exponent; higher gives a smaller, glossier highlight.
+const SHININESS: f64 = 24.0;
+
+/// A directional light for [`RenderMode::Lit`](crate::RenderMode::Lit).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Light {
+    /// Direction the light comes from across the screen, in radians:
+    /// `0` is from the right, `PI / 2` from below.
+    pub azimuth: f64,
+    /// Height of the light above the screen, in radians: `0` grazes the
+    /// surface, `PI / 2` shines straight down on it.
+    pub elevation: f64,
+}
+
+impl Light {
+    /// Unit vector pointing towards the light, with `z` out of the screen.
+    pub fn direction(&self) -> (f64, f64, f64) {
+        let (sin_az, cos_az) = self.azimuth.sin_cos();
+        let (sin_el, cos_el) = self.elevation.sin_cos();
+        (cos_el * cos_az, cos_el * sin_az, sin_el)
+    }
+
+    /// Diffuse and specular brightness of a surface with unit `normal`,
+    /// viewed from straight in front of the screen. The two sum to at most
+    /// about 1.
+    pub fn shade(&self, normal: (f64, f64, f64)) -> (f64, f64) {
+        let (lx, ly, lz) = self.direction();
+        let (nx, ny, nz) = normal;
+        let lambert = (nx * lx + ny * ly + nz * lz).max(0.0);
+
+        // Half-way vector between the light and the viewer at (0, 0, 1)
+        let (hx, hy, hz) = (lx, ly, lz + 1.0);
+        let len = (hx * hx + hy * hy + hz * hz).sqrt();
+        let specular = ((nx * hx + ny * hy + nz * hz) / len).max(0.0).powf(SHININESS);
+
+        (AMBIENT + DIFFUSE * lambert, SPECULAR * specular)
+    }
+}
+
+impl Default for Light {
+    /// From the top left, halfway up.
+    fn default() -> Self {
+        Self {
+            azimuth: -0.75 * PI,
+            elevation: 0.25 * PI,
+        }
+    }
+}
@@ -1,305 +1,279 @@
-use std::f64::consts::PI;
+use std::env;
 use std::io::{self, Write};
+use std::process;
 use std::thread;
 use std::time::{Duration, Instant};
 
-const SCREEN_WIDTH: usize = 80;
-const SCREEN_HEIGHT: usize = 35;
-const THRESHOLD: f64 = 1.0;
-const ASPECT_RATIO: f64 = 2.0;
-
-#[derive(Clone, Copy)]
-enum RenderMode {
-    Gradient,      // Original gradient fill
-    Contour,       // Outline only - shows merging clearly
-    Solid,         // Binary solid fill
-    Blocks,        // Unicode block characters
-    Gooey,         // Emphasizes merge points
+use metaball::{
+    AmbiguousWidth, ColorDepth, DEFAULT_HEIGHT, DEFAULT_WIDTH, Scene, Scene3d, SceneFile,
+};
+
+use animation::Animation;
+use cli::{Command, Options};
+
+mod animation;
+mod cli;
+mod terminal;
+
+const SPEED_STEP: f64 = 1.25;
+const MIN_SPEED: f64 = 0.1;
+const MAX_SPEED: f64 = 10.0;
+
+/// Seconds of simulated time per scene update, whatever the frame rate,
+/// unless the scene needs its own step.
+const SIMULATION_STEP: f64 = 1.0 / 60.0;
+
+/// Longest wall-clock gap simulated in one frame, so a stall (or a
+/// suspended process) doesn't leave a backlog of updates to catch up on.
+const MAX_FRAME_TIME: f64 = 0.25;
+
+/// Playback state driven by the keyboard.
+struct Controls {
+    paused: bool,
+    speed: f64,
+    /// Render blobs between simulation steps instead of where the last
+    /// step left them.
+    interpolate: bool,
+    quit: bool,
 }
 
-impl RenderMode {
-    fn next(self) -> Self {
-        match self {
-            RenderMode::Gradient => RenderMode::Contour,
-            RenderMode::Contour => RenderMode::Solid,
-            RenderMode::Solid => RenderMode::Blocks,
-            RenderMode::Blocks => RenderMode::Gooey,
-            RenderMode::Gooey => RenderMode::Gradient,
-        }
-    }
-
-    fn name(self) -> &'static str {
-        match self {
-            RenderMode::Gradient => "Gradient",
-            RenderMode::Contour => "Contour",
-            RenderMode::Solid => "Solid",
-            RenderMode::Blocks => "Blocks",
-            RenderMode::Gooey => "Gooey",
+impl Controls {
+    /// Handles playback keys, passing the rest on to `scene`.
+    fn handle_key(&mut self, scene: &mut dyn Animation, key: char) {
+        match key {
+            ' ' => self.paused = !self.paused,
+            ']' => self.speed = (self.speed * SPEED_STEP).min(MAX_SPEED),
+            '[' => self.speed = (self.speed / SPEED_STEP).max(MIN_SPEED),
+            'i' => self.interpolate = !self.interpolate,
+            'q' | 'Q' => self.quit = true,
+            _ => scene.handle_key(key),
         }
     }
 }
 
-struct Blob {
-    x: f64,
-    y: f64,
-    radius: f64,
+/// Scene size that fills the terminal, leaving the last row for the status
+/// line, unless overridden on the command line.
+fn scene_size(opts: &Options) -> (usize, usize) {
+    let (width, height) = match terminal::size() {
+        Some((cols, rows)) => (cols, rows.saturating_sub(1).max(1)),
+        None => (DEFAULT_WIDTH, DEFAULT_HEIGHT),
+    };
+    (opts.width.unwrap_or(width), opts.height.unwrap_or(height))
 }
 
-impl Blob {
-    fn new(x: f64, y: f64, radius: f64) -> Self {
-        Self { x, y, radius }
+fn build_scene(opts: &Options) -> Result<Scene, String> {
+    let (width, height) = scene_size(opts);
+    let mut scene = match &opts.scene {
+        Some(path) => SceneFile::load(path)
+            .map_err(|err| format!("{}: {err}", path.display()))?
+            .into_scene(width, height),
+        None => opts.preset.unwrap_or_default().scene(width, height),
+    };
+    if let Some(count) = opts.blobs {
+        scene.set_blob_count(count);
     }
-
-    fn field_at(&self, px: f64, py: f64) -> f64 {
-        let dx = (px - self.x) / ASPECT_RATIO;
-        let dy = py - self.y;
-        let dist_sq = dx * dx + dy * dy;
-        if dist_sq < 0.0001 {
-            return 1000.0;
+    if let Some(seed) = opts.seed {
+        scene.randomize(seed);
+    }
+    if let Some(mode) = opts.mode {
+        scene.set_mode(mode);
+    }
+    if let Some(falloff) = opts.falloff {
+        scene.falloff = falloff;
+    }
+    if let Some(coloring) = opts.coloring {
+        scene.coloring = coloring;
+    }
+    if let Some(ramp) = &opts.gradient_ramp {
+        scene.gradient_ramp = ramp.clone();
+    }
+    if let Some(ramp) = &opts.gooey_ramp {
+        scene.gooey_ramp = ramp.clone();
+    }
+    if let Some(sampling) = opts.sampling {
+        scene.sampling = sampling;
+    }
+    if let Some(line_style) = opts.line_style {
+        scene.line_style = line_style;
+    }
+    if let Some(palette) = &opts.palette {
+        scene.palette = palette.clone();
+    }
+    scene.ambiguous_width = opts.ambiguous_width.unwrap_or_else(AmbiguousWidth::detect);
+    scene.narrow_safe = opts.narrow_safe;
+    if let Some(threshold) = opts.threshold {
+        scene.threshold = threshold;
+    }
+    if opts.no_cycle {
+        scene.cycle_seconds = None;
+    } else if let Some(secs) = opts.cycle_seconds {
+        scene.cycle_seconds = Some(secs);
+    }
+    if opts.physics || opts.gravity.is_some() {
+        if let Some(gravity) = opts.gravity {
+            scene.physics.gravity = gravity;
         }
-        (self.radius * self.radius) / dist_sq
+        scene.physics_enabled = true;
     }
+    Ok(scene)
 }
 
-struct MetaballScene {
-    blobs: Vec<Blob>,
-    time: f64,
-    mode: RenderMode,
-    mode_timer: f64,
-}
-
-impl MetaballScene {
-    fn new() -> Self {
-        Self {
-            blobs: vec![
-                Blob::new(0.0, 0.0, 4.0),
-                Blob::new(0.0, 0.0, 3.0),
-                Blob::new(0.0, 0.0, 3.5),
-                Blob::new(0.0, 0.0, 2.5),
-                Blob::new(0.0, 0.0, 3.2),
-            ],
-            time: 0.0,
-            mode: RenderMode::Gradient,
-            mode_timer: 0.0,
-        }
+fn build_scene3d(opts: &Options) -> Result<Scene3d, String> {
+    if opts.scene.is_some() {
+        return Err("scene files describe 2D scenes and can't be used with --3d".to_string());
     }
-
-    fn update(&mut self, dt: f64) {
-        self.time += dt;
-        self.mode_timer += dt;
-
-        // Cycle modes every 5 seconds
-        if self.mode_timer > 5.0 {
-            self.mode_timer = 0.0;
-            self.mode = self.mode.next();
-        }
-
-        let t = self.time;
-        let cx = SCREEN_WIDTH as f64 / 2.0;
-        let cy = SCREEN_HEIGHT as f64 / 2.0;
-
-        // Main blob - slight wobble at center
-        self.blobs[0].x = cx + (t * 0.5).sin() * 8.0;
-        self.blobs[0].y = cy + (t * 0.7).cos() * 4.0;
-
-        // Orbiting blobs
-        self.blobs[1].x = cx + (t * 1.2).cos() * 20.0;
-        self.blobs[1].y = cy + (t * 1.2).sin() * 10.0;
-
-        self.blobs[2].x = cx + (t * 0.8 + PI * 0.5).cos() * 25.0;
-        self.blobs[2].y = cy + (t * 0.8 + PI * 0.5).sin() * 11.0;
-
-        self.blobs[3].x = cx + (t * 1.5 + PI).cos() * 18.0;
-        self.blobs[3].y = cy + (t * 1.5 + PI).sin() * 8.0;
-
-        self.blobs[4].x = cx + (t * 0.6 + PI * 1.5).cos() * 28.0;
-        self.blobs[4].y = cy + (t * 0.6 + PI * 1.5).sin() * 12.0;
+    if opts.preset.is_some() {
+        return Err("presets describe 2D scenes and can't be used with --3d".to_string());
     }
-
-    fn calculate_field(&self, x: f64, y: f64) -> f64 {
-        self.blobs.iter().map(|b| b.field_at(x, y)).sum()
+    if opts.physics || opts.gravity.is_some() {
+        return Err("--physics is only available for 2D scenes".to_string());
     }
-
-    fn render(&self) -> String {
-        let mut buffer = String::with_capacity(SCREEN_WIDTH * SCREEN_HEIGHT * 4);
-
-        // Pre-calculate field values for edge detection
-        let mut field_grid: Vec<Vec<f64>> = vec![vec![0.0; SCREEN_WIDTH + 1]; SCREEN_HEIGHT + 1];
-        for row in 0..=SCREEN_HEIGHT {
-            for col in 0..=SCREEN_WIDTH {
-                field_grid[row][col] = self.calculate_field(col as f64, row as f64);
-            }
-        }
-
-        for row in 0..SCREEN_HEIGHT {
-            for col in 0..SCREEN_WIDTH {
-                let field = field_grid[row][col];
-                let ch = match self.mode {
-                    RenderMode::Gradient => self.render_gradient(field),
-                    RenderMode::Contour => self.render_contour(&field_grid, row, col),
-                    RenderMode::Solid => self.render_solid(field),
-                    RenderMode::Blocks => self.render_blocks(&field_grid, row, col),
-                    RenderMode::Gooey => self.render_gooey(field),
-                };
-                buffer.push(ch);
-            }
-            buffer.push('\n');
-        }
-
-        buffer
+    // Flags for the 2D render modes, which the 3D scene doesn't have
+    let flat_only = [
+        ("--mode", opts.mode.is_some()),
+        ("--samples", opts.sampling.is_some()),
+        ("--lines", opts.line_style.is_some()),
+        ("--gooey-ramp", opts.gooey_ramp.is_some()),
+        ("--no-cycle", opts.no_cycle),
+        ("--cycle-seconds", opts.cycle_seconds.is_some()),
+    ];
+    if let Some((flag, _)) = flat_only.iter().find(|&&(_, given)| given) {
+        return Err(format!("{flag} is only available for 2D scenes"));
     }
-
-    fn render_gradient(&self, field: f64) -> char {
-        const GRADIENT: &[char] = &[' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'];
-        if field < THRESHOLD * 0.1 {
-            ' '
-        } else if field >= THRESHOLD {
-            let intensity = (field - THRESHOLD).min(3.0) / 3.0;
-            let idx = 5 + (intensity * 4.0) as usize;
-            GRADIENT[idx.min(GRADIENT.len() - 1)]
-        } else {
-            let intensity = field / THRESHOLD;
-            let idx = (intensity * 5.0) as usize;
-            GRADIENT[idx.min(4)]
-        }
+    let (width, height) = scene_size(opts);
+    let mut scene = Scene3d::new(width, height);
+    if let Some(count) = opts.blobs {
+        scene.set_blob_count(count);
     }
-
-    fn render_contour(&self, grid: &[Vec<f64>], row: usize, col: usize) -> char {
-        let field = grid[row][col];
-        let inside = field >= THRESHOLD;
-
-        // Check neighbors for edge detection
-        let neighbors = [
-            (row.saturating_sub(1), col),
-            (row + 1, col),
-            (row, col.saturating_sub(1)),
-            (row, col + 1),
-        ];
-
-        let mut is_edge = false;
-        for (nr, nc) in neighbors {
-            if nr < grid.len() && nc < grid[0].len() {
-                let neighbor_inside = grid[nr][nc] >= THRESHOLD;
-                if inside != neighbor_inside {
-                    is_edge = true;
-                    break;
-                }
-            }
-        }
-
-        if is_edge {
-            // Edge character based on field strength (thicker where blobs merge)
-            if field > THRESHOLD * 1.5 {
-                '@'
-            } else if field > THRESHOLD * 1.2 {
-                '#'
-            } else {
-                'O'
-            }
-        } else if inside {
-            // Inside - show subtle fill
-            '.'
-        } else {
-            ' '
-        }
+    if let Some(seed) = opts.seed {
+        scene.randomize(seed);
     }
-
-    fn render_solid(&self, field: f64) -> char {
-        if field >= THRESHOLD {
-            if field > THRESHOLD * 3.0 {
-                '@'
-            } else if field > THRESHOLD * 2.0 {
-                '#'
-            } else {
-                '*'
-            }
-        } else {
-            ' '
-        }
+    if let Some(falloff) = opts.falloff {
+        scene.falloff = falloff;
     }
-
-    fn render_blocks(&self, grid: &[Vec<f64>], row: usize, col: usize) -> char {
-        // Use 2x2 sub-pixel sampling for smoother edges
-        let mut count = 0;
-        for dy in [0.0, 0.5] {
-            for dx in [0.0, 0.5] {
-                let x = col as f64 + dx;
-                let y = row as f64 + dy;
-                if self.calculate_field(x, y) >= THRESHOLD {
-                    count += 1;
-                }
-            }
-        }
-
-        // Map to block characters
-        let field = grid[row][col];
-        match count {
-            0 => ' ',
-            1 => '░',
-            2 => '▒',
-            3 => '▓',
-            4 => if field > THRESHOLD * 2.0 { '█' } else { '▓' },
-            _ => '█',
-        }
+    if let Some(coloring) = opts.coloring {
+        scene.coloring = coloring;
     }
-
-    fn render_gooey(&self, field: f64) -> char {
-        // Emphasize the "gooey" merge areas with special characters
-        if field < THRESHOLD * 0.3 {
-            ' '
-        } else if field < THRESHOLD * 0.6 {
-            '·'
-        } else if field < THRESHOLD * 0.9 {
-            '○'
-        } else if field < THRESHOLD {
-            '◯'
-        } else if field < THRESHOLD * 1.3 {
-            // Just inside - the "skin"
-            '●'
-        } else if field < THRESHOLD * 2.0 {
-            // Deeper inside
-            '◉'
-        } else {
-            // Core / merge zone
-            '◈'
-        }
+    if let Some(ramp) = &opts.gradient_ramp {
+        scene.ramp = ramp.clone();
+    }
+    if let Some(palette) = &opts.palette {
+        scene.palette = palette.clone();
+    }
+    scene.ambiguous_width = opts.ambiguous_width.unwrap_or_else(AmbiguousWidth::detect);
+    scene.narrow_safe = opts.narrow_safe;
+    if let Some(threshold) = opts.threshold {
+        scene.threshold = threshold;
     }
+    Ok(scene)
 }
 
 fn main() {
-    let mut scene = MetaballScene::new();
+    let opts = match cli::parse(env::args().skip(1)) {
+        Ok(Command::Run(opts)) => opts,
+        Ok(Command::Help) => {
+            println!("{}", cli::usage());
+            return;
+        }
+        Err(err) => {
+            eprintln!("error: {err}\n\nRun with --help for usage.");
+            process::exit(2);
+        }
+    };
+
+    let built: Result<Box<dyn Animation>, String> = if opts.three_d {
+        build_scene3d(&opts).map(|scene| Box::new(scene) as Box<dyn Animation>)
+    } else {
+        build_scene(&opts).map(|scene| Box::new(scene) as Box<dyn Animation>)
+    };
+    let mut scene = match built {
+        Ok(scene) => scene,
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+    };
+    let depth = opts.color_depth.unwrap_or_else(ColorDepth::detect);
     let mut stdout = io::stdout();
 
-    print!("\x1B[?25l"); // Hide cursor
-    print!("\x1B[2J");   // Clear screen
-
-    let frame_duration = Duration::from_millis(33);
+    terminal::watch_resize();
+    terminal::watch_quit();
+    let screen = terminal::Screen::enter(!opts.no_alt_screen).expect("failed to prepare terminal");
+
+    let mut controls = Controls {
+        paused: false,
+        speed: opts.speed,
+        interpolate: opts.interpolate,
+        quit: false,
+    };
+    let frame_duration = Duration::from_secs_f64(1.0 / opts.fps);
     let start_time = Instant::now();
     let mut frame_count: u64 = 0;
+    let mut last_tick = start_time;
+    // Simulated time owed to the scene but not yet covered by a whole step
+    let mut accumulator = 0.0;
+
+    let signal = loop {
+        if let Some(signal) = terminal::quit_signal() {
+            break Some(signal);
+        }
+        for key in screen.read_keys() {
+            controls.handle_key(scene.as_mut(), key);
+        }
+        if controls.quit {
+            break None;
+        }
 
-    loop {
         let frame_start = Instant::now();
 
+        if terminal::take_resize() {
+            let (width, height) = scene_size(&opts);
+            scene.resize(width, height);
+            print!("\x1B[2J");
+        }
+
         print!("\x1B[H");
 
-        scene.update(0.05);
-        let frame = scene.render();
-        print!("{}", frame);
+        let real_dt = frame_start.duration_since(last_tick).as_secs_f64();
+        last_tick = frame_start;
+        let step = scene.timestep().unwrap_or(SIMULATION_STEP);
+        if !controls.paused {
+            accumulator += real_dt.min(MAX_FRAME_TIME) * controls.speed;
+            while accumulator >= step {
+                scene.update(step);
+                accumulator -= step;
+            }
+        }
+        let frame = if controls.interpolate {
+            scene.render_interpolated((accumulator / step).min(1.0))
+        } else {
+            scene.render()
+        };
+        print!("{}", frame.to_ansi(depth));
 
         let elapsed = start_time.elapsed().as_secs_f64();
         frame_count += 1;
         print!(
-            "Metaballs [{}] | Frame: {} | FPS: {:.1}\x1B[K",
-            scene.mode.name(),
+            "{} | Speed: {:.2}x{} | Frame: {} | FPS: {:.1}\x1B[K",
+            scene.status(),
+            controls.speed,
+            if controls.paused { " (paused)" } else { "" },
             frame_count,
             frame_count as f64 / elapsed
         );
 
         stdout.flush().unwrap();
 
+        if opts.frames.is_some_and(|frames| frame_count >= frames) {
+            break None;
+        }
+
         let elapsed_frame = frame_start.elapsed();
         if elapsed_frame < frame_duration {
             thread::sleep(frame_duration - elapsed_frame);
         }
-    }
+    };
+
+    drop(screen);
+    process::exit(signal.map_or(0, |signal| 128 + signal));
 }
@@ -0,0 +1,71 @@
+use std::f64::consts::PI;
+
+use crate::rng::Rng;
+
+/// An elliptical (or Lissajous) path around a point in the viewport.
+///
+/// Centre and radii are fractions of the viewport width and height so the
+/// same orbit fills the screen at any size.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit {
+    pub center_x: f64,
+    pub center_y: f64,
+    pub radius_x: f64,
+    pub radius_y: f64,
+    pub speed_x: f64,
+    pub speed_y: f64,
+    pub phase_x: f64,
+    pub phase_y: f64,
+}
+
+impl Orbit {
+    /// An orbit around the viewport centre whose `x` and `y` move in
+    /// lockstep, tracing an ellipse.
+    pub fn elliptical(radius_x: f64, radius_y: f64, speed: f64, phase: f64) -> Self {
+        Self {
+            center_x: 0.5,
+            center_y: 0.5,
+            radius_x,
+            radius_y,
+            speed_x: speed,
+            speed_y: speed,
+            phase_x: phase,
+            phase_y: phase,
+        }
+    }
+
+    /// A deterministic, varied orbit for the `n`th extra blob, spreading
+    /// phases by the golden angle so new blobs don't bunch up.
+    pub fn nth(n: usize) -> Self {
+        let fract = |k: f64| (n as f64 * k).fract();
+        let radius_x = 0.15 + fract(0.754_877) * 0.2;
+        let radius_y = radius_x * (0.9 + fract(0.569_840) * 0.2);
+        let speed = 0.5 + fract(0.381_966) * 1.0;
+        let phase = n as f64 * PI * (3.0 - 5f64.sqrt());
+        Self::elliptical(radius_x, radius_y, speed, phase)
+    }
+
+    /// A random elliptical orbit within the same ranges as [`Orbit::nth`].
+    pub(crate) fn random(rng: &mut Rng) -> Self {
+        let radius_x = rng.range(0.15, 0.35);
+        let radius_y = radius_x * rng.range(0.9, 1.1);
+        let speed = rng.range(0.5, 1.5);
+        let phase = rng.range(0.0, 2.0 * PI);
+        Self::elliptical(radius_x, radius_y, speed, phase)
+    }
+
+    /// Position at time `t` in a `width` x `height` viewport.
+    pub fn position(&self, t: f64, width: f64, height: f64) -> (f64, f64) {
+        let x = self.center_x * width + (t * self.speed_x + self.phase_x).cos() * self.radius_x * width;
+        let y = self.center_y * height + (t * self.speed_y + self.phase_y).sin() * self.radius_y * height;
+        (x, y)
+    }
+
+    /// Velocity at time `t` in a `width` x `height` viewport, per unit of
+    /// time.
+    pub fn velocity(&self, t: f64, width: f64, height: f64) -> (f64, f64) {
+        let vx = -(t * self.speed_x + self.phase_x).sin() * self.speed_x * self.radius_x * width;
+        let vy = (t * self.speed_y + self.phase_y).cos() * self.speed_y * self.radius_y * height;
+        (vx, vy)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::Rgb;
+
+/// Names of the built-in palettes, in cycle order.
+pub const PALETTES: [&str; 7] = [
+    "heat",
+    "viridis",
+    "magma",
+    "plasma",
+    "fire",
+    "ocean",
+    "catppuccin-mocha",
+];
+
+/// A gradient map from intensity in `[0, 1]` to colour.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Palette {
+    name: String,
+    stops: Vec<(f64, Rgb)>,
+}
+
+impl Palette {
+    /// A palette through `stops`, given as `(position, colour)` with
+    /// positions rising from `0.0` to `1.0`.
+    pub fn new(name: impl Into<String>, stops: Vec<(f64, Rgb)>) -> Result<Self, String> {
+        if stops.len() < 2 {
+            return Err("a palette needs at least two stops".to_string());
+        }
+        if stops.windows(2).any(|pair| pair[1].0 < pair[0].0) {
+            return Err("palette stop positions must not decrease".to_string());
+        }
+        if stops.iter().any(|&(at, _)| !(0.0..=1.0).contains(&at)) {
+            return Err("palette stop positions must be between 0 and 1".to_string());
+        }
+        Ok(Self {
+            name: name.into(),
+            stops,
+        })
+    }
+
+    /// A palette through `colors`, spaced evenly.
+    pub fn from_colors(name: impl Into<String>, colors: &[Rgb]) -> Result<Self, String> {
+        let last = colors.len().saturating_sub(1).max(1) as f64;
+        let stops = colors
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| (i as f64 / last, c))
+            .collect();
+        Self::new(name, stops)
+    }
+
+    /// One of the built-in [`PALETTES`], matched ignoring case.
+    pub fn named(name: &str) -> Option<Self> {
+        let hex: &[&str] = match name.to_ascii_lowercase().as_str() {
+            "viridis" => &["#440154", "#3b528b", "#21918c", "#5ec962", "#fde725"],
+            "magma" => &["#000004", "#3b0f70", "#8c2981", "#de4968", "#fe9f6d", "#fcfdbf"],
+            "plasma" => &["#0d0887", "#6a00a8", "#b12a90", "#e16462", "#fca636", "#f0f921"],
+            "fire" => &["#000000", "#5a0000", "#c81e00", "#ff7800", "#ffd200", "#ffffc8"],
+            "ocean" => &["#000a1e", "#00285a", "#005a96", "#00a0c8", "#64dcf0", "#e6ffff"],
+            // Base, blue, mauve, red, peach and yellow, to match demo.tape
+            "catppuccin-mocha" => &[
+                "#1e1e2e", "#89b4fa", "#cba6f7", "#f38ba8", "#fab387", "#f9e2af",
+            ],
+            "heat" => {
+                // Stops sit where the gradient ramp crosses 0.1, 0.6, 1, 2
+                // and 4 times the threshold
+                let stops = [
+                    (0.05, "#1e1e6e"),
+                    (0.3, "#7828a0"),
+                    (0.5, "#dc3c5a"),
+                    (2.0 / 3.0, "#faa032"),
+                    (1.0, "#fff0aa"),
+                ];
+                let stops = stops.map(|(at, hex)| (at, Rgb::from_hex(hex).unwrap()));
+                return Self::new("heat", stops.to_vec()).ok();
+            }
+            _ => return None,
+        };
+        let colors: Vec<Rgb> = hex.iter().filter_map(|h| Rgb::from_hex(h)).collect();
+        Self::from_colors(name.to_ascii_lowercase(), &colors).ok()
+    }
+
+    /// Parses either a built-in palette name or a comma-separated list of
+    /// `#rrggbb` colours to spread evenly.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if let Some(palette) = Self::named(spec) {
+            return Ok(palette);
+        }
+        if !spec.contains(',') {
+            return Err(format!(
+                "unknown palette '{spec}', expected one of: {} or a list of #rrggbb colours",
+                PALETTES.join(", ")
+            ));
+        }
+        let colors = spec
+            .split(',')
+            .map(|hex| {
+                let hex = hex.trim();
+                Rgb::from_hex(hex).ok_or_else(|| format!("invalid colour '{hex}'"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_colors("custom", &colors)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The next built-in palette after this one, wrapping around. Custom
+    /// palettes go to the first built-in.
+    pub fn next(&self) -> Self {
+        let idx = PALETTES.iter().position(|&n| n == self.name);
+        let next = idx.map_or(0, |i| (i + 1) % PALETTES.len());
+        Self::named(PALETTES[next]).unwrap()
+    }
+
+    /// Colour at intensity `t`, clamped to `[0, 1]`.
+    pub fn sample(&self, t: f64) -> Rgb {
+        let (first, last) = (self.stops[0], self.stops[self.stops.len() - 1]);
+        if t <= first.0 {
+            return first.1;
+        }
+        for pair in self.stops.windows(2) {
+            let ((lo, a), (hi, b)) = (pair[0], pair[1]);
+            if t <= hi {
+                let span = hi - lo;
+                return if span > 0.0 { a.lerp(b, (t - lo) / span) } else { b };
+            }
+        }
+        last.1
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::named("heat").unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_ignoring_case() {
+        for name in PALETTES {
+            assert_eq!(Palette::parse(&name.to_uppercase()).unwrap().name(), name);
+        }
+    }
+
+    #[test]
+    fn parses_colour_lists() {
+        let palette = Palette::parse("#000000, #ff0000,0000ff").unwrap();
+        assert_eq!(palette.name(), "custom");
+        assert_eq!(palette.sample(-1.0), Rgb::new(0, 0, 0));
+        assert_eq!(palette.sample(0.5), Rgb::new(255, 0, 0));
+        assert_eq!(palette.sample(0.75), Rgb::new(128, 0, 128));
+        assert_eq!(palette.sample(2.0), Rgb::new(0, 0, 255));
+    }
+
+    #[test]
+    fn rejects_bad_specs() {
+        assert!(Palette::parse("rainbow").unwrap_err().starts_with("unknown palette 'rainbow', expected one of: heat"));
+        assert_eq!(Palette::parse("#000000,#12345").unwrap_err(), "invalid colour '#12345'");
+        assert_eq!(
+            Palette::new("x", vec![(0.5, Rgb::new(0, 0, 0)), (0.2, Rgb::new(0, 0, 0))]).unwrap_err(),
+            "palette stop positions must not decrease"
+        );
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{ASPECT_RATIO, Blob};
+
+/// Settings for simulating blobs as bodies instead of moving them along
+/// their orbits.
+///
+/// Forces and speeds are in on-screen units, rows per second (squared), so
+/// motion looks the same horizontally and vertically.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Physics {
+    /// Downward acceleration.
+    pub gravity: f64,
+    /// Fraction of velocity lost per second, as an exponential decay rate.
+    pub damping: f64,
+    /// Fraction of speed kept when bouncing off an edge; `1.0` is elastic.
+    pub restitution: f64,
+    /// Peak force between two touching blobs, fading to nothing as they
+    /// separate. Positive pushes them apart, negative pulls them together.
+    pub interaction: f64,
+    /// Seconds of simulated time per integration step; the scene should be
+    /// updated in steps of this size.
+    pub timestep: f64,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            gravity: 30.0,
+            damping: 0.1,
+            restitution: 1.0,
+            interaction: 40.0,
+            timestep: 0.01,
+        }
+    }
+}
+
+impl Physics {
+    /// Advances `blobs` by `dt` seconds inside a `width` x `height`
+    /// viewport, using semi-implicit Euler integration.
+    pub fn step(&self, blobs: &mut [Blob], width: f64, height: f64, dt: f64) {
+        let mut accel = vec![(0.0, self.gravity); blobs.len()];
+
+        // Soft pairwise force: linear in how far two blobs overlap
+        for i in 0..blobs.len() {
+            for j in i + 1..blobs.len() {
+                let dx = (blobs[j].x - blobs[i].x) / ASPECT_RATIO;
+                let dy = blobs[j].y - blobs[i].y;
+                let dist = (dx * dx + dy * dy).sqrt();
+                let reach = blobs[i].radius + blobs[j].radius;
+                if dist >= reach || dist < 1e-9 {
+                    continue;
+                }
+                let force = self.interaction * (1.0 - dist / reach);
+                let (ux, uy) = (dx / dist, dy / dist);
+                accel[i].0 -= ux * force / blobs[i].mass;
+                accel[i].1 -= uy * force / blobs[i].mass;
+                accel[j].0 += ux * force / blobs[j].mass;
+                accel[j].1 += uy * force / blobs[j].mass;
+            }
+        }
+
+        let decay = (-self.damping * dt).exp();
+        for (blob, (ax, ay)) in blobs.iter_mut().zip(accel) {
+            // x is stored in columns, which are 1 / ASPECT_RATIO rows wide
+            blob.vx = (blob.vx + ax * ASPECT_RATIO * dt) * decay;
+            blob.vy = (blob.vy + ay * dt) * decay;
+            blob.x += blob.vx * dt;
+            blob.y += blob.vy * dt;
+
+            let (rx, ry) = (blob.radius * ASPECT_RATIO, blob.radius);
+            bounce(&mut blob.x, &mut blob.vx, rx, width - rx, self.restitution);
+            bounce(&mut blob.y, &mut blob.vy, ry, height - ry, self.restitution);
+        }
+    }
+}
+
+/// Reflects `pos` and `vel` off the walls at `lo` and `hi`.
+fn bounce(pos: &mut f64, vel: &mut f64, lo: f64, hi: f64, restitution: f64) {
+    if hi <= lo {
+        // Viewport smaller than the blob: pin it to the middle
+        *pos = (lo + hi) / 2.0;
+        *vel = 0.0;
+    } else if *pos < lo {
+        *pos = lo + (lo - *pos).min(hi - lo);
+        *vel = vel.abs() * restitution;
+    } else if *pos > hi {
+        *pos = hi - (*pos - hi).min(hi - lo);
+        *vel = -vel.abs() * restitution;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Physics with no forces, so blobs coast.
+    fn coasting() -> Physics {
+        Physics {
+            gravity: 0.0,
+            damping: 0.0,
+            interaction: 0.0,
+            ..Physics::default()
+        }
+    }
+
+    fn moving(x: f64, y: f64, vx: f64, vy: f64) -> Blob {
+        let mut blob = Blob::new(x, y, 2.0);
+        (blob.vx, blob.vy) = (vx, vy);
+        blob
+    }
+
+    #[test]
+    fn coasts_at_constant_velocity() {
+        let mut blobs = [moving(40.0, 10.0, 3.0, -2.0)];
+        for _ in 0..10 {
+            coasting().step(&mut blobs, 80.0, 20.0, 0.1);
+        }
+        assert_eq!((blobs[0].vx, blobs[0].vy), (3.0, -2.0));
+        assert!((blobs[0].x - 43.0).abs() < 1e-9 && (blobs[0].y - 8.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn bounces_off_the_walls() {
+        let (width, height) = (80.0, 20.0);
+        let right = width - 2.0 * ASPECT_RATIO;
+        // One unit short of the right wall and the floor, moving five
+        // units a step towards both
+        let mut blobs = [moving(right - 1.0, height - 3.0, 10.0, 10.0)];
+        coasting().step(&mut blobs, width, height, 0.5);
+        let blob = &blobs[0];
+        assert!((blob.x - (right - 4.0)).abs() < 1e-9, "x {}", blob.x);
+        assert!((blob.y - (height - 6.0)).abs() < 1e-9, "y {}", blob.y);
+        assert_eq!((blob.vx, blob.vy), (-10.0, -10.0));
+
+        let physics = Physics {
+            restitution: 0.5,
+            ..coasting()
+        };
+        let mut blobs = [moving(2.0 * ASPECT_RATIO + 1.0, 10.0, -10.0, 0.0)];
+        physics.step(&mut blobs, width, height, 0.5);
+        assert_eq!(blobs[0].vx, 5.0);
+    }
+
+    #[test]
+    fn stays_inside_the_viewport() {
+        let (width, height) = (30.0, 10.0);
+        let mut blobs = [moving(15.0, 5.0, 500.0, -300.0), moving(10.0, 5.0, -40.0, 90.0)];
+        for _ in 0..200 {
+            Physics::default().step(&mut blobs, width, height, 0.01);
+            for blob in &blobs {
+                let (rx, ry) = (blob.radius * ASPECT_RATIO, blob.radius);
+                assert!((rx..=width - rx).contains(&blob.x), "x {}", blob.x);
+                assert!((ry..=height - ry).contains(&blob.y), "y {}", blob.y);
+            }
+        }
+    }
+}
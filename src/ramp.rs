@@ -0,0 +1,216 @@
+use crate::{AmbiguousWidth, char_width};
+
+/// Names of the built-in ramps.
+pub const RAMPS: [&str; 6] = ["classic", "shades", "dense", "emoji", "gooey", "blocks"];
+
+/// Paul Bourke's 70-character greyscale ramp, light to dark.
+const DENSE: &str =
+    " .'`^\",:;Il!i><~+_-?][}{1)(|\\/tfjrxnuvczXYUJCLQ0OZmwqpdbkhao*#MW&8%B@$";
+
+/// A character ramp: glyphs drawn from a field level upwards, with levels in
+/// multiples of the scene threshold.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Ramp {
+    name: String,
+    steps: Vec<(f64, char)>,
+}
+
+impl Ramp {
+    /// A ramp through `steps`, given as `(level, glyph)` with levels rising.
+    /// Fields below the first level are blank.
+    pub fn new(name: impl Into<String>, steps: Vec<(f64, char)>) -> Result<Self, String> {
+        if steps.is_empty() {
+            return Err("a ramp needs at least one glyph".to_string());
+        }
+        if steps.iter().any(|&(level, _)| !level.is_finite()) {
+            return Err("ramp levels must be finite numbers".to_string());
+        }
+        if steps.windows(2).any(|pair| pair[1].0 < pair[0].0) {
+            return Err("ramp levels must not decrease".to_string());
+        }
+        if let Some(&(_, glyph)) = steps.iter().find(|&&(_, glyph)| char_width(glyph, AmbiguousWidth::Narrow) != 1) {
+            return Err(format!(
+                "ramp glyph '{}' (U+{:04X}) is not one column wide",
+                glyph.escape_default(),
+                glyph as u32
+            ));
+        }
+        Ok(Self {
+            name: name.into(),
+            steps,
+        })
+    }
+
+    /// A ramp through the characters of `glyphs`, spread evenly over the
+    /// same intensity scale as [`Scene::intensity`](crate::Scene::intensity):
+    /// the first half below the threshold, the second up to four times it.
+    /// The first glyph sits at level 0, so a space is put in front unless
+    /// `glyphs` starts with one, keeping empty background blank.
+    pub fn from_glyphs(name: impl Into<String>, glyphs: &str) -> Result<Self, String> {
+        if glyphs.chars().count() < 2 {
+            return Err("a ramp string needs at least two glyphs".to_string());
+        }
+        let glyphs = if glyphs.starts_with(' ') { glyphs.to_string() } else { format!(" {glyphs}") };
+        let count = glyphs.chars().count();
+        let steps = glyphs
+            .chars()
+            .enumerate()
+            .map(|(i, glyph)| {
+                let t = i as f64 / count as f64;
+                let level = if t < 0.5 { t * 2.0 } else { 1.0 + (t - 0.5) * 6.0 };
+                (level, glyph)
+            })
+            .collect();
+        Self::new(name, steps)
+    }
+
+    /// One of the built-in [`RAMPS`], matched ignoring case.
+    pub fn named(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        let steps: &[(f64, char)] = match name.as_str() {
+            // The original gradient: five steps up to the threshold, then
+            // four more reaching `@` at four times it
+            "classic" => &[
+                (0.2, '.'),
+                (0.4, ':'),
+                (0.6, '-'),
+                (0.8, '='),
+                (1.0, '+'),
+                (1.75, '*'),
+                (2.5, '#'),
+                (3.25, '%'),
+                (4.0, '@'),
+            ],
+            "gooey" => &[
+                (0.3, '·'),
+                (0.6, '○'),
+                (0.9, '◯'),
+                // Just inside - the "skin"
+                (1.0, '●'),
+                (1.3, '◉'),
+                // Core / merge zone
+                (2.0, '◈'),
+            ],
+            "shades" => return Self::from_glyphs(name, " ░▒▓█").ok(),
+            "blocks" => return Self::from_glyphs(name, " ▁▂▃▄▅▆▇█").ok(),
+            "dense" => return Self::from_glyphs(name, DENSE).ok(),
+            // Symbols that terminals draw in text style, one column wide
+            "emoji" => return Self::from_glyphs(name, " ·∘○☆★☀♥☻").ok(),
+            _ => return None,
+        };
+        Self::new(name, steps.to_vec()).ok()
+    }
+
+    /// Parses a built-in ramp name, a comma-separated list of `level:glyph`
+    /// pairs, or otherwise a string of glyphs to spread evenly.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if let Some(ramp) = Self::named(spec) {
+            return Ok(ramp);
+        }
+        if let Some(steps) = Self::parse_pairs(spec) {
+            return Self::new("custom", steps);
+        }
+        if spec.chars().count() < 2 {
+            return Err(format!(
+                "unknown ramp '{spec}', expected one of: {}, `level:glyph` pairs or a string of glyphs",
+                RAMPS.join(", ")
+            ));
+        }
+        Self::from_glyphs("custom", spec)
+    }
+
+    /// `None` unless every comma-separated item is a `level:glyph` pair, so
+    /// glyph strings that happen to contain `:` or `,` still parse as strings.
+    fn parse_pairs(spec: &str) -> Option<Vec<(f64, char)>> {
+        spec.split(',')
+            .map(|item| {
+                let (level, glyph) = item.trim_start().split_once(':')?;
+                let mut chars = glyph.chars();
+                let glyph = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                Some((level.trim().parse().ok()?, glyph))
+            })
+            .collect()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Glyph for a brightness `t` in `[0, 1]`, spreading the ramp's
+    /// non-blank glyphs evenly from darkest to brightest.
+    pub fn shade(&self, t: f64) -> char {
+        let glyphs = || self.steps.iter().map(|&(_, glyph)| glyph).filter(|&g| g != ' ');
+        let count = glyphs().count();
+        let idx = (t.clamp(0.0, 1.0) * count as f64) as usize;
+        glyphs().nth(idx.min(count.saturating_sub(1))).unwrap_or(' ')
+    }
+
+    /// Glyph for a field at `level` times the threshold.
+    pub fn glyph(&self, level: f64) -> char {
+        self.steps
+            .iter()
+            .rev()
+            .find(|&&(at, _)| level >= at)
+            .map_or(' ', |&(_, glyph)| glyph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_names_ignoring_case() {
+        for name in RAMPS {
+            assert_eq!(Ramp::parse(&name.to_uppercase()).unwrap().name(), name);
+        }
+    }
+
+    #[test]
+    fn parses_level_glyph_pairs() {
+        let ramp = Ramp::parse("0.5:.,1:o, 2:O").unwrap();
+        assert_eq!(ramp.name(), "custom");
+        assert_eq!(ramp.glyph(0.4), ' ');
+        assert_eq!(ramp.glyph(0.5), '.');
+        assert_eq!(ramp.glyph(1.5), 'o');
+        assert_eq!(ramp.glyph(9.0), 'O');
+        assert_eq!(Ramp::parse("1:a,0.5:b").unwrap_err(), "ramp levels must not decrease");
+    }
+
+    #[test]
+    fn parses_glyph_strings() {
+        let ramp = Ramp::parse(" .oO").unwrap();
+        assert_eq!(ramp.glyph(0.0), ' ');
+        assert_eq!(ramp.glyph(0.5), '.');
+        assert_eq!(ramp.glyph(1.0), 'o');
+        assert_eq!(ramp.glyph(2.5), 'O');
+        // Not every item is a pair, so `:` and `,` are glyphs too
+        assert_eq!(Ramp::parse(".:,x").unwrap().glyph(4.0), 'x');
+    }
+
+    #[test]
+    fn glyph_strings_leave_the_background_blank() {
+        let ramp = Ramp::parse("abc").unwrap();
+        assert_eq!(ramp.glyph(0.0), ' ');
+        assert_eq!(ramp.glyph(0.5), 'a');
+        assert_eq!(ramp.glyph(1.0), 'b');
+        assert_eq!(ramp.glyph(2.5), 'c');
+        assert_eq!(ramp.shade(0.0), 'a');
+    }
+
+    #[test]
+    fn rejects_unknown_and_wide_ramps() {
+        assert!(Ramp::parse("x").unwrap_err().starts_with("unknown ramp 'x', expected one of: classic"));
+        assert_eq!(Ramp::parse(".字").unwrap_err(), "ramp glyph '\\u{5b57}' (U+5B57) is not one column wide");
+    }
+
+    #[test]
+    fn shades_spread_over_non_blank_glyphs() {
+        let ramp = Ramp::parse(" .o").unwrap();
+        assert_eq!(ramp.shade(0.0), '.');
+        assert_eq!(ramp.shade(1.0), 'o');
+    }
+}
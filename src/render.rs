@@ -0,0 +1,723 @@
+use crate::rng::Rng;
+use crate::{ASPECT_RATIO, Cell, Coloring, Frame, Rgb, Scene};
+
+/// How many field samples the fill renderers take per cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Sampling {
+    /// One sample per cell, at its corner.
+    #[default]
+    Single,
+    /// A regular 2x2 grid.
+    Grid2,
+    /// A regular 4x4 grid.
+    Grid4,
+    /// A 4x4 grid with each sample moved randomly within its sub-cell,
+    /// trading the grid's stair-stepping for fine noise.
+    Jittered,
+}
+
+impl Sampling {
+    pub const ALL: [Sampling; 4] = [
+        Sampling::Single,
+        Sampling::Grid2,
+        Sampling::Grid4,
+        Sampling::Jittered,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            Sampling::Single => Sampling::Grid2,
+            Sampling::Grid2 => Sampling::Grid4,
+            Sampling::Grid4 => Sampling::Jittered,
+            Sampling::Jittered => Sampling::Single,
+        }
+    }
+
+    /// Looks up a sampling by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|sampling| sampling.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Sampling::Single => "1",
+            Sampling::Grid2 => "2x2",
+            Sampling::Grid4 => "4x4",
+            Sampling::Jittered => "Jittered",
+        }
+    }
+
+    /// Samples along each axis of a cell.
+    pub fn per_axis(self) -> usize {
+        match self {
+            Sampling::Single => 1,
+            Sampling::Grid2 => 2,
+            Sampling::Grid4 | Sampling::Jittered => 4,
+        }
+    }
+}
+
+/// The field sampled on an `nx` x `ny` grid of sub-cells in every cell of
+/// the viewport, built once per frame so renderers can share it.
+struct FieldBuffer {
+    nx: usize,
+    ny: usize,
+    stride: usize,
+    values: Vec<f64>,
+    threshold: f64,
+}
+
+impl FieldBuffer {
+    /// Samples `scene` at `sampling`, but at least `min` times along each axis.
+    fn new(scene: &Scene, sampling: Sampling, min: usize) -> Self {
+        let n = sampling.per_axis().max(min);
+        // Samples are spread evenly around each cell's corner, so a single
+        // sample lands exactly where the corner-based renderers look
+        Self::grid(scene, n, n, (-0.5, -0.5), sampling == Sampling::Jittered)
+    }
+
+    /// Samples the centres of `nx` x `ny` sub-pixels in each cell, for the
+    /// renderers that shape glyphs to the surface.
+    fn sub_pixels(scene: &Scene, nx: usize, ny: usize) -> Self {
+        Self::grid(scene, nx, ny, (0.0, 0.0), false)
+    }
+
+    /// Samples `scene` at the centre of each of `nx` x `ny` sub-cells per
+    /// cell, shifted by `origin` cells, or at a random point within each
+    /// sub-cell if `jitter` is set.
+    fn grid(scene: &Scene, nx: usize, ny: usize, origin: (f64, f64), jitter: bool) -> Self {
+        let (cols, rows) = (scene.width() * nx, scene.height() * ny);
+        let mut values = Vec::with_capacity(cols * rows);
+        for sy in 0..rows {
+            for sx in 0..cols {
+                let (ox, oy) = if jitter {
+                    // Seeded by position so the pattern holds still between
+                    // frames instead of shimmering
+                    let mut rng = Rng::new((sy * cols + sx) as u64);
+                    (rng.range(0.0, 1.0), rng.range(0.0, 1.0))
+                } else {
+                    (0.5, 0.5)
+                };
+                let x = (sx as f64 + ox) / nx as f64 + origin.0;
+                let y = (sy as f64 + oy) / ny as f64 + origin.1;
+                values.push(scene.calculate_field(x, y));
+            }
+        }
+
+        Self {
+            nx,
+            ny,
+            stride: cols,
+            values,
+            threshold: scene.threshold,
+        }
+    }
+
+    /// The field at sub-cell `(dx, dy)` of the cell at `(col, row)`.
+    fn sample(&self, col: usize, row: usize, dx: usize, dy: usize) -> f64 {
+        self.values[(row * self.ny + dy) * self.stride + col * self.nx + dx]
+    }
+
+    /// A bit per sub-cell of the cell inside the surface, in reading order
+    /// (bit 0 is the top left, bit 1 the one to its right, and so on).
+    fn inside_bits(&self, col: usize, row: usize) -> u32 {
+        let mut bits = 0;
+        for dy in 0..self.ny {
+            for dx in 0..self.nx {
+                if self.sample(col, row, dx, dy) >= self.threshold {
+                    bits |= 1 << (dy * self.nx + dx);
+                }
+            }
+        }
+        bits
+    }
+
+    /// The mean field over the cell's samples and the fraction of them
+    /// inside the surface.
+    fn cell(&self, col: usize, row: usize) -> (f64, f64) {
+        let (mut sum, mut inside) = (0.0, 0);
+        for sy in row * self.ny..(row + 1) * self.ny {
+            let start = sy * self.stride + col * self.nx;
+            for &field in &self.values[start..start + self.nx] {
+                sum += field;
+                if field >= self.threshold {
+                    inside += 1;
+                }
+            }
+        }
+        let count = (self.nx * self.ny) as f64;
+        (sum / count, inside as f64 / count)
+    }
+}
+
+/// Glyphs used to draw [`RenderMode::Marching`] contour lines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LineStyle {
+    /// Rounded box-drawing corners and `─ │ ╱ ╲`.
+    #[default]
+    Box,
+    /// `/ \ | _ -`, for fonts without box-drawing glyphs.
+    Ascii,
+}
+
+impl LineStyle {
+    pub const ALL: [LineStyle; 2] = [LineStyle::Box, LineStyle::Ascii];
+
+    /// Looks up a style by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|style| style.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LineStyle::Box => "Box",
+            LineStyle::Ascii => "Ascii",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderMode {
+    Gradient,      // Original gradient fill
+    Contour,       // Outline only - shows merging clearly
+    Marching,      // Marching-squares contour lines
+    Solid,         // Binary solid fill
+    Blocks,        // Unicode block characters
+    Quadrants,     // 2x2 quadrant blocks shaped to the surface
+    Sextants,      // 2x3 sextant blocks shaped to the surface
+    Braille,       // 2x4 Braille dots per cell
+    HalfBlock,     // Two coloured pixels per cell
+    Gooey,         // Emphasizes merge points
+    Lit,           // Shaded by a directional light
+}
+
+impl RenderMode {
+    /// Every mode, in cycle order.
+    pub const ALL: [RenderMode; 11] = [
+        RenderMode::Gradient,
+        RenderMode::Contour,
+        RenderMode::Marching,
+        RenderMode::Solid,
+        RenderMode::Blocks,
+        RenderMode::Quadrants,
+        RenderMode::Sextants,
+        RenderMode::Braille,
+        RenderMode::HalfBlock,
+        RenderMode::Gooey,
+        RenderMode::Lit,
+    ];
+
+    pub fn next(self) -> Self {
+        match self {
+            RenderMode::Gradient => RenderMode::Contour,
+            RenderMode::Contour => RenderMode::Marching,
+            RenderMode::Marching => RenderMode::Solid,
+            RenderMode::Solid => RenderMode::Blocks,
+            RenderMode::Blocks => RenderMode::Quadrants,
+            RenderMode::Quadrants => RenderMode::Sextants,
+            RenderMode::Sextants => RenderMode::Braille,
+            RenderMode::Braille => RenderMode::HalfBlock,
+            RenderMode::HalfBlock => RenderMode::Gooey,
+            RenderMode::Gooey => RenderMode::Lit,
+            RenderMode::Lit => RenderMode::Gradient,
+        }
+    }
+
+    /// Looks up a mode by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RenderMode::Gradient => "Gradient",
+            RenderMode::Contour => "Contour",
+            RenderMode::Marching => "Marching",
+            RenderMode::Solid => "Solid",
+            RenderMode::Blocks => "Blocks",
+            RenderMode::Quadrants => "Quadrants",
+            RenderMode::Sextants => "Sextants",
+            RenderMode::Braille => "Braille",
+            RenderMode::HalfBlock => "HalfBlock",
+            RenderMode::Gooey => "Gooey",
+            RenderMode::Lit => "Lit",
+        }
+    }
+}
+
+impl Scene {
+    /// Renders the current state of the scene using its active [`RenderMode`].
+    pub fn render(&self) -> Frame {
+        let (width, height) = (self.width(), self.height());
+        let mut frame = Frame::new(width, height);
+
+        // Contour and Marching look at the field on every cell corner,
+        // including the far edges
+        let field_grid: Vec<Vec<f64>> = match self.mode() {
+            RenderMode::Contour | RenderMode::Marching => (0..=height)
+                .map(|row| {
+                    (0..=width)
+                        .map(|col| self.calculate_field(col as f64, row as f64))
+                        .collect()
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        // Every other mode reads one buffer: the fill modes supersampled
+        // (Blocks needs at least 2x2 for its coverage levels), the shaped
+        // modes at their sub-pixels and Lit at the cell corner
+        let samples = match self.mode() {
+            RenderMode::Gradient | RenderMode::Solid | RenderMode::Gooey => {
+                Some(FieldBuffer::new(self, self.sampling, 1))
+            }
+            RenderMode::Blocks => Some(FieldBuffer::new(self, self.sampling, 2)),
+            RenderMode::Quadrants => Some(FieldBuffer::sub_pixels(self, 2, 2)),
+            RenderMode::Sextants => Some(FieldBuffer::sub_pixels(self, 2, 3)),
+            RenderMode::Braille => Some(FieldBuffer::sub_pixels(self, 2, 4)),
+            // Top and bottom halves, down the cell's left edge
+            RenderMode::HalfBlock => Some(FieldBuffer::grid(self, 1, 2, (-0.5, 0.0), false)),
+            RenderMode::Lit => Some(FieldBuffer::new(self, Sampling::Single, 1)),
+            RenderMode::Contour | RenderMode::Marching => None,
+        };
+
+        for row in 0..height {
+            for col in 0..width {
+                let (field, coverage) = match &samples {
+                    Some(buffer) => buffer.cell(col, row),
+                    None => {
+                        let field = field_grid[row][col];
+                        (field, (field >= self.threshold) as u8 as f64)
+                    }
+                };
+                let bits = || samples.as_ref().map_or(0, |buffer| buffer.inside_bits(col, row));
+                let ch = match self.mode() {
+                    RenderMode::Gradient => self.render_gradient(field),
+                    RenderMode::Contour => self.render_contour(&field_grid, row, col),
+                    RenderMode::Marching => self.render_marching(&field_grid, row, col),
+                    RenderMode::Solid => self.render_solid(field, coverage),
+                    RenderMode::Blocks => self.render_blocks(field, coverage),
+                    RenderMode::Quadrants => self.render_quadrants(bits()),
+                    RenderMode::Sextants => self.render_sextants(bits()),
+                    RenderMode::Braille => self.render_braille(bits()),
+                    RenderMode::HalfBlock => {
+                        // Sets its own colours
+                        let buffer = samples.as_ref().expect("HalfBlock samples its halves");
+                        frame.set(col, row, self.render_half_block(buffer, row, col));
+                        continue;
+                    }
+                    RenderMode::Gooey => self.render_gooey(field),
+                    RenderMode::Lit => {
+                        // Sets its own colours
+                        frame.set(col, row, self.render_lit(row, col, field));
+                        continue;
+                    }
+                };
+                let mut cell = Cell::new(ch);
+                if ch != ' ' {
+                    cell.fg = self.cell_color(col as f64, row as f64, field);
+                }
+                frame.set(col, row, cell);
+            }
+        }
+
+        frame.fit_widths(self.ambiguous_width, self.narrow_safe);
+        frame
+    }
+
+    /// Colour of the point `(x, y)`, where the field is `field`, under the
+    /// scene's [`Coloring`].
+    fn cell_color(&self, x: f64, y: f64, field: f64) -> Option<Rgb> {
+        match self.coloring {
+            Coloring::Off => None,
+            Coloring::Field => Some(self.palette.sample(self.intensity(field))),
+            Coloring::Blob => self.dominant_blob(x, y).map(|idx| self.blob_color(idx)),
+            Coloring::Blend => self.blended_color(x, y),
+        }
+    }
+
+    /// Maps `field` onto `[0, 1]` the way the gradient ramp does: the lower
+    /// half covers the glow outside the surface, the upper half up to four
+    /// times the threshold.
+    pub fn intensity(&self, field: f64) -> f64 {
+        let level = field / self.threshold;
+        if level < 1.0 {
+            level.max(0.0) * 0.5
+        } else {
+            0.5 + ((level - 1.0) / 3.0).min(1.0) * 0.5
+        }
+    }
+
+    fn render_gradient(&self, field: f64) -> char {
+        // Negative blobs can pull the field below zero, which every ramp
+        // leaves blank
+        self.gradient_ramp.glyph(field / self.threshold)
+    }
+
+    fn render_contour(&self, grid: &[Vec<f64>], row: usize, col: usize) -> char {
+        let field = grid[row][col];
+        let inside = field >= self.threshold;
+
+        // Check neighbors for edge detection
+        let neighbors = [
+            (row.saturating_sub(1), col),
+            (row + 1, col),
+            (row, col.saturating_sub(1)),
+            (row, col + 1),
+        ];
+
+        let mut is_edge = false;
+        for (nr, nc) in neighbors {
+            if nr < grid.len() && nc < grid[0].len() {
+                let neighbor_inside = grid[nr][nc] >= self.threshold;
+                if inside != neighbor_inside {
+                    is_edge = true;
+                    break;
+                }
+            }
+        }
+
+        if is_edge {
+            // Edge character based on field strength (thicker where blobs merge)
+            if field > self.threshold * 1.5 {
+                '@'
+            } else if field > self.threshold * 1.2 {
+                '#'
+            } else {
+                'O'
+            }
+        } else if inside {
+            // Inside - show subtle fill
+            '.'
+        } else {
+            ' '
+        }
+    }
+
+    /// Marching squares over the cell whose corners are grid points
+    /// `(row, col)` to `(row + 1, col + 1)`: the inside/outside state of the
+    /// four corners picks one of 16 cases, each drawn as the line the
+    /// surface takes through the cell.
+    fn render_marching(&self, grid: &[Vec<f64>], row: usize, col: usize) -> char {
+        let (tl, tr) = (grid[row][col], grid[row][col + 1]);
+        let (bl, br) = (grid[row + 1][col], grid[row + 1][col + 1]);
+        let inside = |v: f64| (v >= self.threshold) as u8;
+        let case = inside(tl) << 3 | inside(tr) << 2 | inside(br) << 1 | inside(bl);
+
+        // Where the surface crosses an edge from `a` to `b`, as a fraction
+        // of the way along it
+        let crossing = |a: f64, b: f64| ((self.threshold - a) / (b - a)).clamp(0.0, 1.0);
+
+        // Two opposite corners inside is ambiguous: the surface either joins
+        // them through the middle or separates them. The field at the cell
+        // centre decides, and either way both lines run the same diagonal.
+        let centre_inside = || {
+            self.calculate_field(col as f64 + 0.5, row as f64 + 0.5) >= self.threshold
+        };
+
+        let ascii = self.line_style == LineStyle::Ascii;
+        match case {
+            0 | 15 => ' ',
+            // A single corner cut off
+            1 | 14 => if ascii { '\\' } else { '╮' },
+            2 | 13 => if ascii { '/' } else { '╭' },
+            4 | 11 => if ascii { '\\' } else { '╰' },
+            7 | 8 => if ascii { '/' } else { '╯' },
+            // Horizontal: left and right edges
+            3 | 12 => {
+                if ascii {
+                    let y = (crossing(tl, bl) + crossing(tr, br)) / 2.0;
+                    if y > 2.0 / 3.0 { '_' } else { '-' }
+                } else {
+                    '─'
+                }
+            }
+            // Vertical: top and bottom edges
+            6 | 9 => if ascii { '|' } else { '│' },
+            // Saddles: bottom-left and top-right inside
+            5 => match (centre_inside(), ascii) {
+                (true, true) => '/',
+                (true, false) => '╱',
+                (false, true) => '\\',
+                (false, false) => '╲',
+            },
+            // Saddles: top-left and bottom-right inside
+            10 => match (centre_inside(), ascii) {
+                (true, true) => '\\',
+                (true, false) => '╲',
+                (false, true) => '/',
+                (false, false) => '╱',
+            },
+            _ => unreachable!("marching squares case is four bits"),
+        }
+    }
+
+    fn render_solid(&self, field: f64, coverage: f64) -> char {
+        // Cells the edge passes through get lighter glyphs by how much of
+        // them is inside
+        if coverage > 0.0 && coverage < 1.0 {
+            return if coverage < 1.0 / 3.0 {
+                '.'
+            } else if coverage < 2.0 / 3.0 {
+                ':'
+            } else {
+                '*'
+            };
+        }
+        if coverage > 0.0 {
+            if field > self.threshold * 3.0 {
+                '@'
+            } else if field > self.threshold * 2.0 {
+                '#'
+            } else {
+                '*'
+            }
+        } else {
+            ' '
+        }
+    }
+
+    fn render_blocks(&self, field: f64, coverage: f64) -> char {
+        // Map sub-pixel coverage to block characters, rounding away from
+        // empty so thin slivers still show
+        let count = if coverage > 0.0 { (coverage * 4.0).round().max(1.0) as usize } else { 0 };
+        match count {
+            0 => ' ',
+            1 => '░',
+            2 => '▒',
+            3 => '▓',
+            4 => if field > self.threshold * 2.0 { '█' } else { '▓' },
+            _ => '█',
+        }
+    }
+
+    /// `bits` has one bit per inside sub-pixel of a 2x2 grid, in reading order.
+    fn render_quadrants(&self, bits: u32) -> char {
+        // Indexed by sub-pixel bits: top left, top right, bottom left,
+        // bottom right
+        const QUADRANTS: [char; 16] = [
+            ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+        ];
+        QUADRANTS[bits as usize]
+    }
+
+    /// `bits` has one bit per inside sub-pixel of a 2x3 grid, in reading order.
+    fn render_sextants(&self, bits: u32) -> char {
+        // U+1FB00 onwards covers every pattern in sub-pixel bit order, except
+        // the four that already exist as block elements
+        match bits {
+            0 => ' ',
+            0b010101 => '▌',
+            0b101010 => '▐',
+            0b111111 => '█',
+            bits => {
+                let skipped = (bits > 0b010101) as u32 + (bits > 0b101010) as u32;
+                char::from_u32(0x1FB00 + bits - 1 - skipped).unwrap_or('█')
+            }
+        }
+    }
+
+    /// `bits` has one bit per inside sub-pixel of a 2x4 grid, in reading order.
+    fn render_braille(&self, bits: u32) -> char {
+        // Dot for each sub-pixel in reading order; the bottom row was added
+        // to Braille later, hence the jump to 0x40
+        const DOTS: [u32; 8] = [0x01, 0x08, 0x02, 0x10, 0x04, 0x20, 0x40, 0x80];
+
+        let dots: u32 = DOTS
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| bits & (1 << i) != 0)
+            .map(|(_, dot)| dot)
+            .sum();
+        if dots == 0 {
+            ' '
+        } else {
+            char::from_u32(0x2800 + dots).unwrap_or(' ')
+        }
+    }
+
+    /// Two vertically stacked pixels per cell: `▀` paints the top one in the
+    /// foreground colour and the bottom one in the background colour.
+    /// Halves are square on screen, so no aspect correction is lost.
+    /// `buffer` holds the field at the centre of each half.
+    fn render_half_block(&self, buffer: &FieldBuffer, row: usize, col: usize) -> Cell {
+        let pixel = |dy: usize| {
+            let (x, y) = (col as f64, row as f64 + (dy as f64 + 0.5) / 2.0);
+            let field = buffer.sample(col, row, 0, dy);
+            (field >= self.threshold).then(|| self.cell_color(x, y, field))
+        };
+
+        match (pixel(0), pixel(1)) {
+            (None, None) => Cell::BLANK,
+            // Without colour both halves share the foreground
+            (Some(None), Some(None)) => Cell::new('█'),
+            (Some(top), None) => Cell { fg: top, ..Cell::new('▀') },
+            // Leave the empty top half in the terminal's own background
+            (None, Some(bottom)) => Cell { fg: bottom, ..Cell::new('▄') },
+            (Some(top), Some(bottom)) => Cell {
+                ch: '▀',
+                fg: top,
+                bg: bottom,
+            },
+        }
+    }
+
+    /// Shades the surface as if the field were a height map lit by the
+    /// scene's [`Light`](crate::Light), choosing glyphs from the gradient
+    /// ramp by brightness.
+    fn render_lit(&self, row: usize, col: usize, field: f64) -> Cell {
+        if field < self.threshold {
+            return Cell::BLANK;
+        }
+        let (x, y) = (col as f64, row as f64);
+        let (gx, gy) = self.calculate_gradient(x, y);
+
+        // Height is -threshold / field: steep at the edge, levelling off
+        // towards the cores instead of spiking into them. Its gradient is
+        // threshold * grad(field) / field², with x scaled back to on-screen
+        // units so slopes match vertically and horizontally.
+        let scale = self.threshold / (field * field);
+        let (hx, hy) = (gx * ASPECT_RATIO * scale, gy * scale);
+        let len = (hx * hx + hy * hy + 1.0).sqrt();
+        let normal = (-hx / len, -hy / len, 1.0 / len);
+
+        let (diffuse, specular) = self.light.shade(normal);
+        let brightness = (diffuse + specular).min(1.0);
+        let mut cell = Cell::new(self.gradient_ramp.shade(brightness));
+        cell.fg = match self.coloring {
+            Coloring::Off => None,
+            Coloring::Field => Some(self.palette.sample(brightness)),
+            // Darken the blob's colour away from the light and wash it
+            // towards white in the highlight
+            _ => self
+                .cell_color(x, y, field)
+                .map(|color| color.lit(diffuse, specular)),
+        };
+        cell
+    }
+
+    fn render_gooey(&self, field: f64) -> char {
+        // Emphasize the "gooey" merge areas with special characters
+        self.gooey_ramp.glyph(field / self.threshold)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Blob;
+
+    /// A one-cell scene, optionally with a blob covering the cell centre.
+    fn cell_scene(centre_inside: bool) -> Scene {
+        let blobs = if centre_inside { vec![Blob::new(0.5, 0.5, 4.0)] } else { Vec::new() };
+        Scene::with_blobs(1, 1, blobs)
+    }
+
+    /// Corner fields for a marching-squares `case`: top left, top right,
+    /// bottom right and bottom left from the high bit down.
+    fn corners(case: u8) -> Vec<Vec<f64>> {
+        let field = |bit: u8| if case & bit != 0 { 2.0 } else { 0.0 };
+        vec![vec![field(8), field(4)], vec![field(1), field(2)]]
+    }
+
+    #[test]
+    fn marching_cases_draw_the_crossed_edges() {
+        let scene = cell_scene(false);
+        // Every case but the saddles, with its complement
+        let expected = [
+            (0, ' '),
+            (1, '╮'),
+            (2, '╭'),
+            (3, '─'),
+            (4, '╰'),
+            (6, '│'),
+            (7, '╯'),
+        ];
+        for (case, glyph) in expected {
+            assert_eq!(scene.render_marching(&corners(case), 0, 0), glyph, "case {case}");
+            assert_eq!(scene.render_marching(&corners(15 - case), 0, 0), glyph, "case {}", 15 - case);
+        }
+    }
+
+    #[test]
+    fn marching_saddles_follow_the_centre() {
+        // Joined through the centre, the lines run along the inside corners;
+        // split, they cut those corners off
+        assert_eq!(cell_scene(true).render_marching(&corners(5), 0, 0), '╱');
+        assert_eq!(cell_scene(false).render_marching(&corners(5), 0, 0), '╲');
+        assert_eq!(cell_scene(true).render_marching(&corners(10), 0, 0), '╲');
+        assert_eq!(cell_scene(false).render_marching(&corners(10), 0, 0), '╱');
+
+        let mut scene = cell_scene(true);
+        scene.line_style = LineStyle::Ascii;
+        assert_eq!(scene.render_marching(&corners(5), 0, 0), '/');
+        assert_eq!(scene.render_marching(&corners(10), 0, 0), '\\');
+    }
+
+    #[test]
+    fn marching_ascii_lines_sit_at_the_crossing() {
+        let mut scene = cell_scene(false);
+        scene.line_style = LineStyle::Ascii;
+        assert_eq!(scene.render_marching(&corners(1), 0, 0), '\\');
+        assert_eq!(scene.render_marching(&corners(8), 0, 0), '/');
+        assert_eq!(scene.render_marching(&corners(6), 0, 0), '|');
+        // Crossing a third of the way down the sides, then near the bottom
+        assert_eq!(scene.render_marching(&[vec![1.5, 1.5], vec![0.0, 0.0]], 0, 0), '-');
+        assert_eq!(scene.render_marching(&[vec![1.5, 1.5], vec![0.9, 0.9]], 0, 0), '_');
+    }
+
+    #[test]
+    fn braille_bits_map_to_dots() {
+        let scene = cell_scene(false);
+        // Sub-pixels in reading order are dots 1, 4, 2, 5, 3, 6, 7, 8
+        let dots = ['⠁', '⠈', '⠂', '⠐', '⠄', '⠠', '⡀', '⢀'];
+        for (bit, dot) in dots.into_iter().enumerate() {
+            assert_eq!(scene.render_braille(1 << bit), dot, "bit {bit}");
+        }
+        assert_eq!(scene.render_braille(0), ' ');
+        assert_eq!(scene.render_braille(0xFF), '⣿');
+    }
+
+    #[test]
+    fn quadrant_bits_map_to_glyphs() {
+        let scene = cell_scene(false);
+        assert_eq!(scene.render_quadrants(0b0001), '▘');
+        assert_eq!(scene.render_quadrants(0b0010), '▝');
+        assert_eq!(scene.render_quadrants(0b0100), '▖');
+        assert_eq!(scene.render_quadrants(0b1000), '▗');
+        assert_eq!(scene.render_quadrants(0b0110), '▞');
+        assert_eq!(scene.render_quadrants(0b1001), '▚');
+        assert_eq!(scene.render_quadrants(0b0011), '▀');
+        assert_eq!(scene.render_quadrants(0b0101), '▌');
+        assert_eq!(scene.render_quadrants(0b1111), '█');
+    }
+
+    #[test]
+    fn sextant_bits_skip_the_block_elements() {
+        let scene = cell_scene(false);
+        // Named by the sextants they fill, numbered in reading order
+        let expected = [
+            (0b000001, '\u{1FB00}'), // 1
+            (0b000010, '\u{1FB01}'), // 2
+            (0b000011, '\u{1FB02}'), // 12
+            (0b010100, '\u{1FB13}'), // 35
+            (0b010101, '▌'),
+            (0b010110, '\u{1FB14}'), // 235
+            (0b101000, '\u{1FB26}'), // 46
+            (0b101001, '\u{1FB27}'), // 146
+            (0b101010, '▐'),
+            (0b101011, '\u{1FB28}'), // 1246
+            (0b111110, '\u{1FB3B}'), // 23456
+            (0b111111, '█'),
+        ];
+        for (bits, glyph) in expected {
+            assert_eq!(scene.render_sextants(bits), glyph, "bits {bits:06b}");
+        }
+    }
+}
@@ -0,0 +1,389 @@
+use std::f64::consts::PI;
+
+use crate::color::{self, default_blob_color};
+use crate::rng::Rng;
+use crate::{
+    AmbiguousWidth, Blob, Coloring, DEFAULT_HEIGHT, DEFAULT_WIDTH, Falloff, Lava, Light,
+    LineStyle, Orbit, Palette, Physics, Ramp, RenderMode, Rgb, Sampling, THRESHOLD,
+};
+
+/// Default time spent in each render mode before cycling to the next.
+pub const MODE_CYCLE_SECONDS: f64 = 5.0;
+
+/// Radius of blobs added with [`Scene::add_blob`], cycled by index.
+const EXTRA_RADII: [f64; 4] = [3.0, 2.5, 3.5, 2.8];
+
+/// A built-in scene.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// Five blobs on elliptical orbits, cycling through the render modes.
+    #[default]
+    Orbits,
+    /// Wax heated from below, rising, cooling and sinking under physics.
+    LavaLamp,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 2] = [Preset::Orbits, Preset::LavaLamp];
+
+    pub fn next(self) -> Self {
+        match self {
+            Preset::Orbits => Preset::LavaLamp,
+            Preset::LavaLamp => Preset::Orbits,
+        }
+    }
+
+    /// Looks up a preset by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|preset| preset.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Preset::Orbits => "orbits",
+            Preset::LavaLamp => "lava-lamp",
+        }
+    }
+
+    /// Builds this preset at `width` x `height` cells.
+    pub fn scene(self, width: usize, height: usize) -> Scene {
+        match self {
+            Preset::Orbits => Scene::new(width, height),
+            Preset::LavaLamp => Scene::lava_lamp(width, height),
+        }
+    }
+}
+
+/// A set of blobs animated along their orbits, cycling render modes over time.
+#[derive(Clone)]
+pub struct Scene {
+    pub blobs: Vec<Blob>,
+    /// Field value at which a point is considered inside the surface.
+    pub threshold: f64,
+    /// Kernel for blobs that don't set their own.
+    pub falloff: Falloff,
+    /// How rendered cells are coloured.
+    pub coloring: Coloring,
+    /// Gradient map for [`Coloring::Field`].
+    pub palette: Palette,
+    /// Glyphs for [`RenderMode::Marching`].
+    pub line_style: LineStyle,
+    /// Glyphs for [`RenderMode::Gradient`].
+    pub gradient_ramp: Ramp,
+    /// Glyphs for [`RenderMode::Gooey`].
+    pub gooey_ramp: Ramp,
+    /// Light for [`RenderMode::Lit`].
+    pub light: Light,
+    /// How the terminal draws ambiguous-width glyphs, so rows can be kept
+    /// to `width` columns.
+    pub ambiguous_width: AmbiguousWidth,
+    /// Swap every glyph that might be drawn wide for an ASCII fallback.
+    pub narrow_safe: bool,
+    /// Field samples per cell for the Gradient, Solid, Blocks and Gooey modes.
+    pub sampling: Sampling,
+    /// Seconds between automatic mode changes; `None` keeps the current mode.
+    pub cycle_seconds: Option<f64>,
+    /// How blobs behave while [`physics_enabled`](Self::physics_enabled).
+    pub physics: Physics,
+    /// Simulate blobs as bodies instead of moving them along their orbits.
+    /// When turned on, moving blobs set off at their orbit's velocity and
+    /// stationary ones at their own.
+    pub physics_enabled: bool,
+    /// Heats, cools, splits and merges blobs while physics is on.
+    pub lava: Option<Lava>,
+    /// Whether blobs are being simulated, so turning physics on can be noticed.
+    simulating: bool,
+    /// Blob positions before the latest update, for interpolation.
+    previous: Vec<(f64, f64)>,
+    mode: RenderMode,
+    width: usize,
+    height: usize,
+    time: f64,
+    mode_timer: f64,
+}
+
+impl Scene {
+    /// Creates the default five-blob scene sized to `width` x `height` cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_blobs(
+            width,
+            height,
+            vec![
+                // Main blob - slight wobble at center
+                Blob::new(0.0, 0.0, 4.0).with_orbit(Orbit {
+                    speed_y: 0.7,
+                    phase_y: PI * 0.5,
+                    ..Orbit::elliptical(0.1, 0.114, 0.5, -PI * 0.5)
+                }),
+                // Orbiting blobs
+                Blob::new(0.0, 0.0, 3.0).with_orbit(Orbit::elliptical(0.25, 0.286, 1.2, 0.0)),
+                Blob::new(0.0, 0.0, 3.5).with_orbit(Orbit::elliptical(0.3125, 0.314, 0.8, PI * 0.5)),
+                Blob::new(0.0, 0.0, 2.5).with_orbit(Orbit::elliptical(0.225, 0.229, 1.5, PI)),
+                Blob::new(0.0, 0.0, 3.2).with_orbit(Orbit::elliptical(0.35, 0.343, 0.6, PI * 1.5)),
+            ],
+        )
+    }
+
+    /// Creates a lava lamp: a pool of cold wax at the bottom of a `width` x
+    /// `height` viewport, heated from below.
+    pub fn lava_lamp(width: usize, height: usize) -> Self {
+        const POOL: [(f64, f64); 6] =
+            [(0.2, 2.6), (0.35, 3.2), (0.5, 2.4), (0.6, 3.0), (0.75, 2.8), (0.85, 2.2)];
+        let (w, h) = (width as f64, height as f64);
+        let wax = Rgb::new(255, 96, 32);
+        let blobs = POOL
+            .iter()
+            .map(|&(x, radius)| {
+                Blob::new(x * w, h - radius, radius)
+                    .with_color(wax)
+                    .with_mass(radius * radius)
+            })
+            .collect();
+
+        let mut scene = Self::with_blobs(width, height, blobs);
+        scene.falloff = Falloff::Compact;
+        scene.coloring = Coloring::Field;
+        scene.palette = Palette::named("fire").unwrap();
+        scene.cycle_seconds = None;
+        scene.physics = Physics {
+            gravity: 6.0,
+            damping: 0.6,
+            restitution: 0.2,
+            interaction: -3.0,
+            ..Physics::default()
+        };
+        scene.physics_enabled = true;
+        scene.lava = Some(Lava::default());
+        scene.set_mode(RenderMode::Gooey);
+        scene
+    }
+
+    /// Creates a scene of `width` x `height` cells containing `blobs`.
+    pub fn with_blobs(width: usize, height: usize, blobs: Vec<Blob>) -> Self {
+        let mut scene = Self {
+            blobs,
+            threshold: THRESHOLD,
+            falloff: Falloff::default(),
+            coloring: Coloring::default(),
+            palette: Palette::default(),
+            line_style: LineStyle::default(),
+            gradient_ramp: Ramp::named("classic").unwrap(),
+            gooey_ramp: Ramp::named("gooey").unwrap(),
+            light: Light::default(),
+            ambiguous_width: AmbiguousWidth::default(),
+            narrow_safe: false,
+            sampling: Sampling::default(),
+            cycle_seconds: Some(MODE_CYCLE_SECONDS),
+            physics: Physics::default(),
+            physics_enabled: false,
+            lava: None,
+            simulating: false,
+            previous: Vec::new(),
+            mode: RenderMode::Gradient,
+            width,
+            height,
+            time: 0.0,
+            mode_timer: 0.0,
+        };
+        scene.layout();
+        scene
+    }
+
+    /// Changes the viewport size and re-lays out the blobs for it.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+        self.layout();
+        // Don't slide blobs across from where the old size put them
+        self.previous.clear();
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Seconds of simulated time elapsed since the scene was created.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    pub fn mode(&self) -> RenderMode {
+        self.mode
+    }
+
+    /// Switches to `mode` and restarts the automatic mode cycle timer.
+    pub fn set_mode(&mut self, mode: RenderMode) {
+        self.mode = mode;
+        self.mode_timer = 0.0;
+    }
+
+    /// Adds an orbiting blob, varying its size and path with the blob count.
+    pub fn add_blob(&mut self) {
+        let n = self.blobs.len();
+        let radius = EXTRA_RADII[n % EXTRA_RADII.len()];
+        let orbit = Orbit::nth(n);
+        let (w, h) = (self.width as f64, self.height as f64);
+        let (x, y) = orbit.position(self.time, w, h);
+        let (vx, vy) = orbit.velocity(self.time, w, h);
+        self.blobs.push(
+            Blob::new(x, y, radius)
+                .with_orbit(orbit)
+                .with_velocity(vx, vy),
+        );
+        self.previous.clear();
+    }
+
+    /// Removes the most recently added blob, if any.
+    pub fn remove_blob(&mut self) {
+        self.blobs.pop();
+        self.previous.clear();
+    }
+
+    /// Adds or removes blobs until there are exactly `count`.
+    pub fn set_blob_count(&mut self, count: usize) {
+        self.blobs.truncate(count);
+        while self.blobs.len() < count {
+            self.add_blob();
+        }
+    }
+
+    /// Replaces every blob's radius and orbit with ones drawn from `seed`, so
+    /// the same seed always produces the same choreography.
+    pub fn randomize(&mut self, seed: u64) {
+        let mut rng = Rng::new(seed);
+        for blob in &mut self.blobs {
+            blob.radius = rng.range(2.5, 4.0);
+            blob.orbit = Some(Orbit::random(&mut rng));
+        }
+        self.layout();
+        self.previous.clear();
+    }
+
+    /// Step that [`update`](Self::update) should be called with for the
+    /// physics to stay stable and even, while physics is on.
+    pub fn timestep(&self) -> Option<f64> {
+        self.physics_enabled.then_some(self.physics.timestep)
+    }
+
+    /// Advances the scene by `dt` seconds; under physics, this is one
+    /// integration step.
+    pub fn update(&mut self, dt: f64) {
+        self.previous.clear();
+        self.previous.extend(self.blobs.iter().map(|b| (b.x, b.y)));
+        self.time += dt;
+        self.mode_timer += dt;
+
+        if self.cycle_seconds.is_some_and(|secs| self.mode_timer > secs) {
+            self.mode_timer = 0.0;
+            self.mode = self.mode.next();
+        }
+
+        if self.physics_enabled {
+            if !self.simulating {
+                self.launch();
+            }
+            let (w, h) = (self.width as f64, self.height as f64);
+            if let Some(lava) = self.lava
+                && lava.step(&mut self.blobs, w, h, dt)
+            {
+                // Indices no longer match, so there's nothing to blend from
+                self.previous.clear();
+            }
+            self.physics.step(&mut self.blobs, w, h, dt);
+        } else {
+            self.simulating = false;
+            self.layout();
+        }
+    }
+
+    /// Starts simulating: each moving blob takes its orbit's current velocity.
+    fn launch(&mut self) {
+        let (w, h) = (self.width as f64, self.height as f64);
+        for blob in &mut self.blobs {
+            let velocity = blob.orbit.map(|orbit| orbit.velocity(self.time, w, h));
+            if let Some(v) = velocity.filter(|&v| v != (0.0, 0.0)) {
+                (blob.vx, blob.vy) = v;
+            }
+        }
+        self.simulating = true;
+    }
+
+    /// Places each orbiting blob on its path for the current time and
+    /// viewport, unless physics is moving them.
+    fn layout(&mut self) {
+        if self.physics_enabled {
+            return;
+        }
+        let (w, h) = (self.width as f64, self.height as f64);
+        for blob in &mut self.blobs {
+            if let Some(orbit) = blob.orbit {
+                (blob.x, blob.y) = orbit.position(self.time, w, h);
+            }
+        }
+    }
+
+    /// A copy of the scene with each blob `alpha` of the way from where it
+    /// was before the last [`update`](Self::update) to where it is now, for
+    /// rendering between updates. Blobs stay put if any were added, removed,
+    /// split or merged since.
+    pub fn interpolated(&self, alpha: f64) -> Scene {
+        let mut scene = self.clone();
+        if self.previous.len() == self.blobs.len() {
+            for (blob, &(x, y)) in scene.blobs.iter_mut().zip(&self.previous) {
+                blob.x = x + (blob.x - x) * alpha;
+                blob.y = y + (blob.y - y) * alpha;
+            }
+        }
+        scene
+    }
+
+    /// Sum of every blob's field contribution at `(x, y)`.
+    pub fn calculate_field(&self, x: f64, y: f64) -> f64 {
+        self.blobs.iter().map(|b| b.field_with(x, y, self.falloff)).sum()
+    }
+
+    /// Gradient of [`calculate_field`](Self::calculate_field) at `(x, y)`,
+    /// per column and per row.
+    pub fn calculate_gradient(&self, x: f64, y: f64) -> (f64, f64) {
+        self.blobs
+            .iter()
+            .map(|b| b.gradient_with(x, y, self.falloff))
+            .fold((0.0, 0.0), |(sx, sy), (gx, gy)| (sx + gx, sy + gy))
+    }
+
+    /// Index of the blob contributing most to the field at `(x, y)`, if any
+    /// contributes positively.
+    pub fn dominant_blob(&self, x: f64, y: f64) -> Option<usize> {
+        color::dominant(self.blobs.iter().map(|b| b.field_with(x, y, self.falloff)))
+    }
+
+    /// The colour of the blob at `index`, falling back to a default by index.
+    pub fn blob_color(&self, index: usize) -> Rgb {
+        self.blobs[index]
+            .color
+            .unwrap_or_else(|| default_blob_color(index))
+    }
+
+    /// Blob colours at `(x, y)` weighted by each blob's positive field
+    /// contribution, or `None` if no blob contributes.
+    pub fn blended_color(&self, x: f64, y: f64) -> Option<Rgb> {
+        color::blend(
+            self.blobs
+                .iter()
+                .enumerate()
+                .map(|(idx, blob)| (self.blob_color(idx), blob.field_with(x, y, self.falloff))),
+        )
+    }
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    }
+}
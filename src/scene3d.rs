@@ -0,0 +1,548 @@
+use std::f64::consts::PI;
+use std::ops::{Add, Mul, Sub};
+
+use crate::color::{self, default_blob_color};
+use crate::rng::Rng;
+use crate::{
+    ASPECT_RATIO, AmbiguousWidth, Cell, Coloring, DEFAULT_HEIGHT, DEFAULT_WIDTH, Falloff, Frame,
+    Light, Palette, Ramp, Rgb, THRESHOLD,
+};
+
+/// Steps each ray takes per radius of the smallest positive blob; more
+/// catches thinner necks between blobs at the cost of speed.
+const STEPS_PER_RADIUS: f64 = 4.0;
+
+/// Most steps a single ray takes before giving up.
+const MAX_STEPS: usize = 256;
+
+/// Bisection passes refining a hit once a step crosses the surface.
+const REFINE_STEPS: usize = 10;
+
+/// A point or direction in world space: `x` right, `y` up, `z` towards the
+/// default camera.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Vec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl Vec3 {
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+    pub fn dot(self, other: Vec3) -> f64 {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn length(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+
+    /// This vector scaled to length 1, or unchanged if it has no length.
+    pub fn normalized(self) -> Vec3 {
+        let len = self.length();
+        if len > 0.0 { self * (1.0 / len) } else { self }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, other: Vec3) -> Vec3 {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, k: f64) -> Vec3 {
+        Vec3::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+/// A circular path around `center`, tipped out of the `x`/`y` plane.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orbit3d {
+    pub center: Vec3,
+    pub radius: f64,
+    /// Radians per second.
+    pub speed: f64,
+    pub phase: f64,
+    /// Rotation of the orbit's plane about the `x` axis, in radians.
+    pub tilt: f64,
+    /// Rotation of the orbit's plane about the `y` axis, in radians.
+    pub yaw: f64,
+}
+
+impl Orbit3d {
+    /// The `n`th of a family of orbits spread evenly by the golden angle, so
+    /// added blobs never share a path.
+    pub fn nth(n: usize) -> Self {
+        let fract = |k: f64| (n as f64 * k).fract();
+        Self {
+            center: Vec3::default(),
+            radius: 1.0 + fract(0.754_877) * 1.5,
+            speed: 0.5 + fract(0.381_966),
+            phase: n as f64 * PI * (3.0 - 5f64.sqrt()),
+            tilt: (fract(0.569_840) - 0.5) * PI,
+            yaw: fract(0.236_068) * PI,
+        }
+    }
+
+    fn random(rng: &mut Rng) -> Self {
+        Self {
+            center: Vec3::default(),
+            radius: rng.range(1.0, 2.5),
+            speed: rng.range(0.5, 1.5),
+            phase: rng.range(0.0, 2.0 * PI),
+            tilt: rng.range(-0.5 * PI, 0.5 * PI),
+            yaw: rng.range(0.0, PI),
+        }
+    }
+
+    /// Position at time `t`.
+    pub fn position(&self, t: f64) -> Vec3 {
+        let (sin, cos) = (t * self.speed + self.phase).sin_cos();
+        let (sin_tilt, cos_tilt) = self.tilt.sin_cos();
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (x, y, z) = (cos, sin * cos_tilt, sin * sin_tilt);
+        let p = Vec3::new(x * cos_yaw + z * sin_yaw, y, -x * sin_yaw + z * cos_yaw);
+        self.center + p * self.radius
+    }
+}
+
+/// A spherical metaball in a [`Scene3d`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Blob3d {
+    pub position: Vec3,
+    pub radius: f64,
+    /// `1.0` adds to the field, `-1.0` subtracts from it.
+    pub sign: f64,
+    pub strength: f64,
+    pub color: Option<Rgb>,
+    pub orbit: Option<Orbit3d>,
+}
+
+impl Blob3d {
+    pub fn new(position: Vec3, radius: f64) -> Self {
+        Self {
+            position,
+            radius,
+            sign: 1.0,
+            strength: 1.0,
+            color: None,
+            orbit: None,
+        }
+    }
+
+    pub fn with_orbit(mut self, orbit: Orbit3d) -> Self {
+        self.orbit = Some(orbit);
+        self
+    }
+
+    pub fn with_color(mut self, color: Rgb) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn negative(mut self) -> Self {
+        self.sign = -1.0;
+        self
+    }
+
+    /// Field contribution at `p` under `falloff`.
+    pub fn field_at(&self, p: Vec3, falloff: Falloff) -> f64 {
+        let d = p - self.position;
+        self.sign * self.strength * falloff.eval(d.dot(d), self.radius)
+    }
+
+    /// Gradient of [`field_at`](Self::field_at) at `p`.
+    pub fn gradient_at(&self, p: Vec3, falloff: Falloff) -> Vec3 {
+        let d = p - self.position;
+        let slope = falloff.slope(d.dot(d), self.radius);
+        d * (2.0 * self.sign * self.strength * slope)
+    }
+}
+
+/// A pinhole camera.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    pub eye: Vec3,
+    pub target: Vec3,
+    /// Vertical field of view, in radians.
+    pub fov: f64,
+}
+
+impl Camera {
+    /// Right, up and forward unit vectors for the view, with world `y` up.
+    fn basis(&self) -> (Vec3, Vec3, Vec3) {
+        let forward = (self.target - self.eye).normalized();
+        let right = forward.cross(Vec3::new(0.0, 1.0, 0.0)).normalized();
+        let up = right.cross(forward);
+        (right, up, forward)
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self {
+            eye: Vec3::new(0.0, 0.0, 7.0),
+            target: Vec3::default(),
+            fov: PI / 3.0,
+        }
+    }
+}
+
+/// Metaballs in three dimensions, ray-marched into a [`Frame`] and shaded
+/// like [`RenderMode::Lit`](crate::RenderMode::Lit).
+#[derive(Clone)]
+pub struct Scene3d {
+    pub blobs: Vec<Blob3d>,
+    /// Field value at which a point is considered inside the surface.
+    pub threshold: f64,
+    pub falloff: Falloff,
+    /// How rendered cells are coloured.
+    pub coloring: Coloring,
+    /// Gradient map for [`Coloring::Field`], sampled by brightness.
+    pub palette: Palette,
+    /// Glyphs, picked dark to bright.
+    pub ramp: Ramp,
+    /// Light direction relative to the camera, as on the 2D screen.
+    pub light: Light,
+    pub camera: Camera,
+    /// How the terminal draws ambiguous-width glyphs, so rows can be kept
+    /// to `width` columns.
+    pub ambiguous_width: AmbiguousWidth,
+    /// Swap every glyph that might be drawn wide for an ASCII fallback.
+    pub narrow_safe: bool,
+    width: usize,
+    height: usize,
+    time: f64,
+    /// Blob positions before the latest update, for interpolation.
+    previous: Vec<Vec3>,
+}
+
+impl Scene3d {
+    /// Creates a five-blob scene sized to `width` x `height` cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        let blobs = (0..5)
+            .map(|n| {
+                let radius = if n == 0 { 1.4 } else { 1.0 };
+                Blob3d::new(Vec3::default(), radius).with_orbit(Orbit3d::nth(n))
+            })
+            .collect();
+        Self::with_blobs(width, height, blobs)
+    }
+
+    /// Creates a scene of `width` x `height` cells containing `blobs`.
+    pub fn with_blobs(width: usize, height: usize, blobs: Vec<Blob3d>) -> Self {
+        let mut scene = Self {
+            blobs,
+            threshold: THRESHOLD,
+            falloff: Falloff::Compact,
+            coloring: Coloring::default(),
+            palette: Palette::default(),
+            ramp: Ramp::named("classic").unwrap(),
+            light: Light::default(),
+            camera: Camera::default(),
+            ambiguous_width: AmbiguousWidth::default(),
+            narrow_safe: false,
+            width,
+            height,
+            time: 0.0,
+            previous: Vec::new(),
+        };
+        scene.layout();
+        scene
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.width = width;
+        self.height = height;
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Seconds of simulated time elapsed since the scene was created.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Adds an orbiting blob on the next unused path.
+    pub fn add_blob(&mut self) {
+        let n = self.blobs.len();
+        self.blobs.push(Blob3d::new(Vec3::default(), 1.0).with_orbit(Orbit3d::nth(n)));
+        self.layout();
+    }
+
+    /// Removes the most recently added blob, if any.
+    pub fn remove_blob(&mut self) {
+        self.blobs.pop();
+    }
+
+    /// Adds or removes blobs until there are exactly `count`.
+    pub fn set_blob_count(&mut self, count: usize) {
+        self.blobs.truncate(count);
+        while self.blobs.len() < count {
+            self.add_blob();
+        }
+    }
+
+    /// Replaces every blob's radius and orbit with ones drawn from `seed`.
+    pub fn randomize(&mut self, seed: u64) {
+        let mut rng = Rng::new(seed);
+        for blob in &mut self.blobs {
+            blob.radius = rng.range(0.8, 1.4);
+            blob.orbit = Some(Orbit3d::random(&mut rng));
+        }
+        self.layout();
+    }
+
+    /// Advances the animation by `dt` seconds.
+    pub fn update(&mut self, dt: f64) {
+        self.previous.clear();
+        self.previous.extend(self.blobs.iter().map(|b| b.position));
+        self.time += dt;
+        self.layout();
+    }
+
+    /// A copy of the scene with each blob `alpha` of the way from where it
+    /// was before the last [`update`](Self::update) to where it is now.
+    pub fn interpolated(&self, alpha: f64) -> Scene3d {
+        let mut scene = self.clone();
+        if self.previous.len() == self.blobs.len() {
+            for (blob, &prev) in scene.blobs.iter_mut().zip(&self.previous) {
+                blob.position = prev + (blob.position - prev) * alpha;
+            }
+        }
+        scene
+    }
+
+    fn layout(&mut self) {
+        for blob in &mut self.blobs {
+            if let Some(orbit) = blob.orbit {
+                blob.position = orbit.position(self.time);
+            }
+        }
+    }
+
+    /// Sum of every blob's field contribution at `p`.
+    pub fn calculate_field(&self, p: Vec3) -> f64 {
+        self.blobs.iter().map(|b| b.field_at(p, self.falloff)).sum()
+    }
+
+    /// Gradient of [`calculate_field`](Self::calculate_field) at `p`.
+    pub fn calculate_gradient(&self, p: Vec3) -> Vec3 {
+        self.blobs
+            .iter()
+            .fold(Vec3::default(), |sum, b| sum + b.gradient_at(p, self.falloff))
+    }
+
+    /// A sphere enclosing everywhere the field could reach the threshold,
+    /// as `(center, radius)`.
+    fn bounds(&self) -> Option<(Vec3, f64)> {
+        let positive: Vec<&Blob3d> = self.blobs.iter().filter(|b| b.sign > 0.0).collect();
+        if positive.is_empty() {
+            return None;
+        }
+        let sum = positive.iter().fold(Vec3::default(), |sum, b| sum + b.position);
+        let center = sum * (1.0 / positive.len() as f64);
+        // The fields can only sum to the threshold where at least one of
+        // them reaches its share of it
+        let share = self.threshold / positive.len() as f64;
+        let radius = positive
+            .iter()
+            .map(|b| {
+                let reach = self.falloff.reach(b.radius, share / b.strength);
+                (b.position - center).length() + reach
+            })
+            .fold(0.0, f64::max);
+        Some((center, radius))
+    }
+
+    /// Marches from `origin` along unit `dir` between distances `near` and
+    /// `far`, returning the first point on the surface, or the `near` point
+    /// if it is already inside.
+    fn march(&self, origin: Vec3, dir: Vec3, near: f64, far: f64, step: f64) -> Option<Vec3> {
+        let steps = (((far - near) / step).ceil() as usize).min(MAX_STEPS);
+        let step = (far - near) / steps.max(1) as f64;
+        let mut prev = near;
+        if self.calculate_field(origin + dir * prev) >= self.threshold {
+            return Some(origin + dir * prev);
+        }
+        for i in 1..=steps {
+            let t = near + step * i as f64;
+            if self.calculate_field(origin + dir * t) >= self.threshold {
+                // Bisect between the last point outside and this one inside
+                let (mut lo, mut hi) = (prev, t);
+                for _ in 0..REFINE_STEPS {
+                    let mid = (lo + hi) / 2.0;
+                    if self.calculate_field(origin + dir * mid) >= self.threshold {
+                        hi = mid;
+                    } else {
+                        lo = mid;
+                    }
+                }
+                return Some(origin + dir * hi);
+            }
+            prev = t;
+        }
+        None
+    }
+
+    /// The colour of the blob at `index`, falling back to a default by index.
+    fn blob_color(&self, index: usize) -> Rgb {
+        self.blobs[index]
+            .color
+            .unwrap_or_else(|| default_blob_color(index))
+    }
+
+    /// Blob colours at `p` weighted by each blob's positive contribution.
+    fn blended_color(&self, p: Vec3) -> Option<Rgb> {
+        color::blend(
+            self.blobs
+                .iter()
+                .enumerate()
+                .map(|(idx, blob)| (self.blob_color(idx), blob.field_at(p, self.falloff))),
+        )
+    }
+
+    /// Colour of the blob contributing most at `p`.
+    fn dominant_color(&self, p: Vec3) -> Option<Rgb> {
+        color::dominant(self.blobs.iter().map(|b| b.field_at(p, self.falloff)))
+            .map(|idx| self.blob_color(idx))
+    }
+
+    /// Ray-marches one ray per cell and shades the surface it hits.
+    pub fn render(&self) -> Frame {
+        let (width, height) = (self.width, self.height);
+        let mut frame = Frame::new(width, height);
+        let Some((center, bound)) = self.bounds() else {
+            return frame;
+        };
+        let step = self
+            .blobs
+            .iter()
+            .filter(|b| b.sign > 0.0)
+            .map(|b| b.radius)
+            .fold(f64::INFINITY, f64::min)
+            / STEPS_PER_RADIUS;
+
+        let (right, up, forward) = self.camera.basis();
+        let half_height = height as f64 / 2.0;
+        let tan = (self.camera.fov / 2.0).tan();
+        let eye = self.camera.eye;
+
+        for row in 0..height {
+            for col in 0..width {
+                // A column is 1 / ASPECT_RATIO as wide as a row is tall
+                let u = (col as f64 + 0.5 - width as f64 / 2.0) / ASPECT_RATIO / half_height * tan;
+                let v = (row as f64 + 0.5 - half_height) / half_height * tan;
+                let dir = (forward + right * u - up * v).normalized();
+
+                // Only march where the ray crosses the bounding sphere
+                let oc = eye - center;
+                let b = oc.dot(dir);
+                let disc = b * b - (oc.dot(oc) - bound * bound);
+                if disc < 0.0 {
+                    continue;
+                }
+                let (near, far) = ((-b - disc.sqrt()).max(0.0), -b + disc.sqrt());
+                if far <= near {
+                    continue;
+                }
+                let Some(hit) = self.march(eye, dir, near, far, step) else {
+                    continue;
+                };
+
+                // The field rises inwards, so the outward normal is against
+                // its gradient; `Light` expects screen axes, y down
+                let n = (self.calculate_gradient(hit) * -1.0).normalized();
+                let normal = (n.dot(right), -n.dot(up), -n.dot(forward));
+                let (diffuse, specular) = self.light.shade(normal);
+                let brightness = (diffuse + specular).min(1.0);
+
+                let mut cell = Cell::new(self.ramp.shade(brightness));
+                cell.fg = match self.coloring {
+                    Coloring::Off => None,
+                    Coloring::Field => Some(self.palette.sample(brightness)),
+                    Coloring::Blob | Coloring::Blend => {
+                        let base = if self.coloring == Coloring::Blob {
+                            self.dominant_color(hit)
+                        } else {
+                            self.blended_color(hit)
+                        };
+                        base.map(|color| color.lit(diffuse, specular))
+                    }
+                };
+                frame.set(col, row, cell);
+            }
+        }
+
+        frame.fit_widths(self.ambiguous_width, self.narrow_safe);
+        frame
+    }
+}
+
+impl Default for Scene3d {
+    fn default() -> Self {
+        Self::new(DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drawn(scene: &Scene3d) -> usize {
+        let frame = scene.render();
+        frame.rows().flatten().filter(|cell| cell.ch != ' ').count()
+    }
+
+    #[test]
+    fn low_thresholds_still_draw_infinite_kernels() {
+        for falloff in [Falloff::InverseSquare, Falloff::Gaussian] {
+            let mut scene = Scene3d::new(40, 20);
+            scene.falloff = falloff;
+            for threshold in [1.0, 0.2, 0.1] {
+                scene.threshold = threshold;
+                assert!(drawn(&scene) > 0, "{} drew nothing at {threshold}", falloff.name());
+            }
+        }
+    }
+
+    #[test]
+    fn a_stronger_blob_is_drawn_larger() {
+        let blob = Blob3d::new(Vec3::default(), 0.5);
+        let mut scene = Scene3d::with_blobs(40, 20, vec![blob]);
+        scene.falloff = Falloff::InverseSquare;
+        let weak = drawn(&scene);
+        scene.blobs[0].strength = 4.0;
+        assert!(drawn(&scene) > weak);
+    }
+}
@@ -0,0 +1,776 @@
+//! Scene description files.
+//!
+//! Scenes are written in a small TOML subset: top-level `key = value` pairs
+//! for scene settings, followed by one `[[blob]]` table per blob, optionally
+//! `[[stop]]` tables defining a custom palette and optionally a `[physics]`
+//! table that simulates the blobs instead of moving them along their orbits.
+//! Values are numbers, `"strings"` or booleans, and `#` starts a comment.
+//!
+//! ```toml
+//! mode = "gooey"
+//! falloff = "compact"
+//! coloring = "field"
+//! threshold = 1.0
+//! cycle_seconds = 5
+//!
+//! [[blob]]
+//! radius = 4.0
+//! radius_x = 0.25   # orbit radii as fractions of the viewport
+//! radius_y = 0.3
+//! speed = 1.2       # radians per second
+//! phase = 1.57
+//! mass = 2.0        # used by [physics]
+//!
+//! [physics]
+//! gravity = 30      # rows per second squared
+//! restitution = 0.9
+//!
+//! [[stop]]
+//! at = 0.0
+//! color = "#1e1e2e"
+//!
+//! [[stop]]
+//! at = 1.0
+//! color = "#f5c2e7"
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::{
+    Blob, Capsule, Coloring, Ellipse, Falloff, Light, LineStyle, MODE_CYCLE_SECONDS, Orbit,
+    Palette, Physics, Ramp, RenderMode, Rgb, RoundedRect, Sampling, Scene, Shape, THRESHOLD,
+};
+
+#[derive(Debug)]
+pub enum SceneFileError {
+    Io(io::Error),
+    /// A syntax or validation error, with the 1-based line it was found on
+    /// where one applies.
+    Invalid { line: Option<usize>, message: String },
+}
+
+impl SceneFileError {
+    fn at(line: usize, message: impl Into<String>) -> Self {
+        SceneFileError::Invalid {
+            line: Some(line),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SceneFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneFileError::Io(err) => write!(f, "{err}"),
+            SceneFileError::Invalid {
+                line: Some(line),
+                message,
+            } => write!(f, "line {line}: {message}"),
+            SceneFileError::Invalid { line: None, message } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for SceneFileError {}
+
+impl From<io::Error> for SceneFileError {
+    fn from(err: io::Error) -> Self {
+        SceneFileError::Io(err)
+    }
+}
+
+/// A parsed scene description, ready to be turned into a [`Scene`].
+#[derive(Clone, Debug)]
+pub struct SceneFile {
+    pub threshold: f64,
+    pub falloff: Falloff,
+    pub coloring: Coloring,
+    pub palette: Palette,
+    pub line_style: LineStyle,
+    pub gradient_ramp: Option<Ramp>,
+    pub gooey_ramp: Option<Ramp>,
+    pub sampling: Sampling,
+    pub light: Light,
+    pub mode: RenderMode,
+    pub cycle_seconds: Option<f64>,
+    pub physics: Option<Physics>,
+    pub blobs: Vec<Blob>,
+}
+
+/// The table that `key = value` lines currently belong to.
+enum Section {
+    Scene,
+    Blob,
+    Stop,
+    Physics,
+}
+
+impl SceneFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SceneFileError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(src: &str) -> Result<Self, SceneFileError> {
+        let mut file = SceneFile {
+            threshold: THRESHOLD,
+            falloff: Falloff::default(),
+            coloring: Coloring::default(),
+            palette: Palette::default(),
+            line_style: LineStyle::default(),
+            gradient_ramp: None,
+            gooey_ramp: None,
+            sampling: Sampling::default(),
+            light: Light::default(),
+            mode: RenderMode::Gradient,
+            cycle_seconds: Some(MODE_CYCLE_SECONDS),
+            physics: None,
+            blobs: Vec::new(),
+        };
+        let mut scene_keys: Vec<(String, usize)> = Vec::new();
+        let mut physics_keys: Vec<(String, usize)> = Vec::new();
+        let mut specs: Vec<BlobSpec> = Vec::new();
+        let mut stops: Vec<StopSpec> = Vec::new();
+        let mut section = Section::Scene;
+
+        for (idx, raw) in src.lines().enumerate() {
+            let line = idx + 1;
+            let text = strip_comment(raw).trim();
+            if text.is_empty() {
+                continue;
+            }
+
+            if text.starts_with('[') {
+                section = match text {
+                    "[[blob]]" => {
+                        specs.push(BlobSpec::new(line));
+                        Section::Blob
+                    }
+                    "[[stop]]" => {
+                        stops.push(StopSpec::new(line));
+                        Section::Stop
+                    }
+                    "[physics]" => {
+                        if file.physics.is_some() {
+                            return Err(SceneFileError::at(line, "duplicate table [physics]"));
+                        }
+                        file.physics = Some(Physics::default());
+                        Section::Physics
+                    }
+                    _ => return Err(SceneFileError::at(line, format!("unknown table {text}"))),
+                };
+                continue;
+            }
+
+            let (key, value) = text
+                .split_once('=')
+                .ok_or_else(|| SceneFileError::at(line, "expected `key = value`"))?;
+            let key = key.trim();
+            let value = Value::parse(value.trim())
+                .ok_or_else(|| SceneFileError::at(line, format!("invalid value for '{key}'")))?;
+
+            let (context, seen) = match section {
+                Section::Scene => (String::new(), &mut scene_keys),
+                Section::Blob => (
+                    format!("blob {}: ", specs.len()),
+                    &mut specs.last_mut().unwrap().keys,
+                ),
+                Section::Stop => (
+                    format!("stop {}: ", stops.len()),
+                    &mut stops.last_mut().unwrap().keys,
+                ),
+                Section::Physics => ("physics: ".to_string(), &mut physics_keys),
+            };
+            if seen.iter().any(|(k, _)| k == key) {
+                return Err(SceneFileError::at(line, format!("{context}duplicate key '{key}'")));
+            }
+            seen.push((key.to_string(), line));
+
+            let result = match section {
+                Section::Scene => file.set(key, value),
+                Section::Blob => specs.last_mut().unwrap().set(key, value),
+                Section::Stop => stops.last_mut().unwrap().set(key, value),
+                Section::Physics => set_physics(file.physics.as_mut().unwrap(), key, value),
+            };
+            result.map_err(|message| SceneFileError::at(line, format!("{context}{message}")))?;
+        }
+
+        if specs.is_empty() {
+            return Err(SceneFileError::Invalid {
+                line: None,
+                message: "scene must define at least one [[blob]]".to_string(),
+            });
+        }
+        for (n, spec) in specs.into_iter().enumerate() {
+            file.blobs.push(spec.build(n + 1)?);
+        }
+
+        if let Some(first) = stops.first() {
+            let line = first.line;
+            if scene_keys.iter().any(|(k, _)| k == "palette") {
+                return Err(SceneFileError::at(
+                    line,
+                    "use either 'palette' or [[stop]] tables, not both",
+                ));
+            }
+            let stops = stops
+                .into_iter()
+                .enumerate()
+                .map(|(n, stop)| stop.build(n + 1))
+                .collect::<Result<Vec<_>, _>>()?;
+            file.palette =
+                Palette::new("custom", stops).map_err(|message| SceneFileError::at(line, message))?;
+        }
+        Ok(file)
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        match key {
+            "threshold" => self.threshold = value.positive(key)?,
+            "mode" => {
+                let name = value.string(key)?;
+                self.mode = RenderMode::from_name(&name).ok_or_else(|| {
+                    let names: Vec<&str> = RenderMode::ALL.iter().map(|m| m.name()).collect();
+                    format!("unknown mode '{name}', expected one of: {}", names.join(", "))
+                })?;
+            }
+            "falloff" => self.falloff = value.falloff(key)?,
+            "coloring" => {
+                let name = value.string(key)?;
+                self.coloring = Coloring::from_name(&name).ok_or_else(|| {
+                    let names: Vec<&str> = Coloring::ALL.iter().map(|c| c.name()).collect();
+                    format!("unknown coloring '{name}', expected one of: {}", names.join(", "))
+                })?;
+            }
+            "palette" => self.palette = Palette::parse(&value.string(key)?)?,
+            "lines" => {
+                let name = value.string(key)?;
+                self.line_style = LineStyle::from_name(&name).ok_or_else(|| {
+                    let names: Vec<&str> = LineStyle::ALL.iter().map(|l| l.name()).collect();
+                    format!("unknown line style '{name}', expected one of: {}", names.join(", "))
+                })?;
+            }
+            "gradient_ramp" => self.gradient_ramp = Some(Ramp::parse(&value.string(key)?)?),
+            "gooey_ramp" => self.gooey_ramp = Some(Ramp::parse(&value.string(key)?)?),
+            "light_azimuth" => self.light.azimuth = value.number(key)?,
+            "light_elevation" => self.light.elevation = value.number(key)?,
+            "samples" => {
+                let name = value.string(key)?;
+                self.sampling = Sampling::from_name(&name).ok_or_else(|| {
+                    let names: Vec<&str> = Sampling::ALL.iter().map(|s| s.name()).collect();
+                    format!("unknown sampling '{name}', expected one of: {}", names.join(", "))
+                })?;
+            }
+            "cycle" => {
+                if !value.boolean(key)? {
+                    self.cycle_seconds = None;
+                }
+            }
+            "cycle_seconds" => {
+                let secs = value.positive(key)?;
+                self.cycle_seconds = self.cycle_seconds.map(|_| secs);
+            }
+            _ => return Err(format!("unknown scene key '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// Builds a `width` x `height` scene from this description.
+    pub fn into_scene(self, width: usize, height: usize) -> Scene {
+        let mut scene = Scene::with_blobs(width, height, self.blobs);
+        scene.threshold = self.threshold;
+        scene.falloff = self.falloff;
+        scene.coloring = self.coloring;
+        scene.palette = self.palette;
+        scene.line_style = self.line_style;
+        scene.sampling = self.sampling;
+        scene.light = self.light;
+        if let Some(ramp) = self.gradient_ramp {
+            scene.gradient_ramp = ramp;
+        }
+        if let Some(ramp) = self.gooey_ramp {
+            scene.gooey_ramp = ramp;
+        }
+        scene.cycle_seconds = self.cycle_seconds;
+        if let Some(physics) = self.physics {
+            scene.physics = physics;
+            scene.physics_enabled = true;
+        }
+        scene.set_mode(self.mode);
+        scene
+    }
+}
+
+/// Applies one `key = value` line of the `[physics]` table.
+fn set_physics(physics: &mut Physics, key: &str, value: Value) -> Result<(), String> {
+    match key {
+        "gravity" => physics.gravity = value.number(key)?,
+        "damping" => physics.damping = value.non_negative(key)?,
+        "restitution" => {
+            physics.restitution = match value.number(key)? {
+                r if (0.0..=1.0).contains(&r) => r,
+                _ => return Err("'restitution' must be between 0 and 1".to_string()),
+            }
+        }
+        "interaction" => physics.interaction = value.number(key)?,
+        "timestep" => physics.timestep = value.positive(key)?,
+        _ => return Err(format!("unknown physics key '{key}'")),
+    }
+    Ok(())
+}
+
+/// Names accepted for a blob's `shape` key.
+const SHAPES: [&str; 4] = ["circle", "ellipse", "capsule", "rect"];
+
+/// The keys of one `[[blob]]` table, collected before validation.
+struct BlobSpec {
+    line: usize,
+    /// Each key given, with the line it was on.
+    keys: Vec<(String, usize)>,
+    radius: Option<f64>,
+    shape: String,
+    stretch_x: Option<f64>,
+    stretch_y: Option<f64>,
+    angle: Option<f64>,
+    half_length: Option<f64>,
+    half_width: Option<f64>,
+    half_height: Option<f64>,
+    sign: f64,
+    strength: f64,
+    color: Option<Rgb>,
+    falloff: Option<Falloff>,
+    center_x: f64,
+    center_y: f64,
+    radius_x: f64,
+    radius_y: f64,
+    speed: Option<f64>,
+    speed_x: Option<f64>,
+    speed_y: Option<f64>,
+    phase: Option<f64>,
+    phase_x: Option<f64>,
+    phase_y: Option<f64>,
+    vx: f64,
+    vy: f64,
+    mass: f64,
+}
+
+impl BlobSpec {
+    fn new(line: usize) -> Self {
+        Self {
+            line,
+            keys: Vec::new(),
+            radius: None,
+            shape: "circle".to_string(),
+            stretch_x: None,
+            stretch_y: None,
+            angle: None,
+            half_length: None,
+            half_width: None,
+            half_height: None,
+            sign: 1.0,
+            strength: 1.0,
+            color: None,
+            falloff: None,
+            center_x: 0.5,
+            center_y: 0.5,
+            radius_x: 0.0,
+            radius_y: 0.0,
+            speed: None,
+            speed_x: None,
+            speed_y: None,
+            phase: None,
+            phase_x: None,
+            phase_y: None,
+            vx: 0.0,
+            vy: 0.0,
+            mass: 1.0,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        match key {
+            "radius" => self.radius = Some(value.positive(key)?),
+            "shape" => {
+                let name = value.string(key)?.to_ascii_lowercase();
+                if !SHAPES.contains(&name.as_str()) {
+                    return Err(format!(
+                        "unknown shape '{name}', expected one of: {}",
+                        SHAPES.join(", ")
+                    ));
+                }
+                self.shape = name;
+            }
+            "stretch_x" => self.stretch_x = Some(value.positive(key)?),
+            "stretch_y" => self.stretch_y = Some(value.positive(key)?),
+            "angle" => self.angle = Some(value.number(key)?),
+            "half_length" => self.half_length = Some(value.non_negative(key)?),
+            "half_width" => self.half_width = Some(value.non_negative(key)?),
+            "half_height" => self.half_height = Some(value.non_negative(key)?),
+            "sign" => {
+                self.sign = match value.number(key)? {
+                    s if s == 1.0 || s == -1.0 => s,
+                    _ => return Err("'sign' must be 1 or -1".to_string()),
+                }
+            }
+            "strength" => self.strength = value.positive(key)?,
+            "color" => self.color = Some(value.color(key)?),
+            "falloff" => self.falloff = Some(value.falloff(key)?),
+            "center_x" => self.center_x = value.number(key)?,
+            "center_y" => self.center_y = value.number(key)?,
+            "radius_x" => self.radius_x = value.non_negative(key)?,
+            "radius_y" => self.radius_y = value.non_negative(key)?,
+            "speed" => self.speed = Some(value.number(key)?),
+            "speed_x" => self.speed_x = Some(value.number(key)?),
+            "speed_y" => self.speed_y = Some(value.number(key)?),
+            "phase" => self.phase = Some(value.number(key)?),
+            "phase_x" => self.phase_x = Some(value.number(key)?),
+            "phase_y" => self.phase_y = Some(value.number(key)?),
+            "vx" => self.vx = value.number(key)?,
+            "vy" => self.vy = value.number(key)?,
+            "mass" => self.mass = value.positive(key)?,
+            _ => return Err(format!("unknown blob key '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// The line `key` was given on, or the `[[blob]]` header's if it wasn't.
+    fn line_of(&self, key: &str) -> usize {
+        self.keys
+            .iter()
+            .find(|(k, _)| k == key)
+            .map_or(self.line, |&(_, line)| line)
+    }
+
+    /// Validates the table and builds the `n`th (1-based) blob from it.
+    fn build(self, n: usize) -> Result<Blob, SceneFileError> {
+        let radius = self.radius.ok_or_else(|| {
+            SceneFileError::at(self.line, format!("blob {n}: missing required key 'radius'"))
+        })?;
+        let shape = self.shape(n)?;
+        let speed = self.speed.unwrap_or(0.0);
+        let phase = self.phase.unwrap_or(0.0);
+
+        let mut blob = Blob::new(0.0, 0.0, radius).with_shape(shape).with_orbit(Orbit {
+            center_x: self.center_x,
+            center_y: self.center_y,
+            radius_x: self.radius_x,
+            radius_y: self.radius_y,
+            speed_x: self.speed_x.unwrap_or(speed),
+            speed_y: self.speed_y.unwrap_or(speed),
+            phase_x: self.phase_x.unwrap_or(phase),
+            phase_y: self.phase_y.unwrap_or(phase),
+        });
+        blob.sign = self.sign;
+        blob.strength = self.strength;
+        blob.color = self.color;
+        blob.falloff = self.falloff;
+        blob.vx = self.vx;
+        blob.vy = self.vy;
+        blob.mass = self.mass;
+        Ok(blob)
+    }
+
+    /// Builds the blob's shape, rejecting keys that belong to other shapes.
+    fn shape(&self, n: usize) -> Result<Shape, SceneFileError> {
+        let allowed: &[&str] = match self.shape.as_str() {
+            "ellipse" => &["stretch_x", "stretch_y", "angle"],
+            "capsule" => &["half_length", "angle"],
+            "rect" => &["half_width", "half_height", "angle"],
+            _ => &[],
+        };
+        let given = [
+            ("stretch_x", self.stretch_x),
+            ("stretch_y", self.stretch_y),
+            ("angle", self.angle),
+            ("half_length", self.half_length),
+            ("half_width", self.half_width),
+            ("half_height", self.half_height),
+        ];
+        if let Some((key, _)) = given
+            .iter()
+            .find(|(key, value)| value.is_some() && !allowed.contains(key))
+        {
+            return Err(SceneFileError::at(
+                self.line_of(key),
+                format!("blob {n}: '{key}' does not apply to {} shapes", self.shape),
+            ));
+        }
+
+        let required = |key: &str, value: Option<f64>| {
+            value.ok_or_else(|| {
+                SceneFileError::at(
+                    self.line_of("shape"),
+                    format!("blob {n}: {} shapes require '{key}'", self.shape),
+                )
+            })
+        };
+        let angle = self.angle.unwrap_or(0.0);
+        Ok(match self.shape.as_str() {
+            "ellipse" => Shape::Ellipse(Ellipse {
+                stretch_x: self.stretch_x.unwrap_or(1.0),
+                stretch_y: self.stretch_y.unwrap_or(1.0),
+                angle,
+            }),
+            "capsule" => Shape::Capsule(Capsule {
+                half_length: required("half_length", self.half_length)?,
+                angle,
+            }),
+            "rect" => Shape::RoundedRect(RoundedRect {
+                half_width: required("half_width", self.half_width)?,
+                half_height: required("half_height", self.half_height)?,
+                angle,
+            }),
+            _ => Shape::Circle,
+        })
+    }
+}
+
+/// The keys of one `[[stop]]` table.
+struct StopSpec {
+    line: usize,
+    keys: Vec<(String, usize)>,
+    at: Option<f64>,
+    color: Option<Rgb>,
+}
+
+impl StopSpec {
+    fn new(line: usize) -> Self {
+        Self {
+            line,
+            keys: Vec::new(),
+            at: None,
+            color: None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: Value) -> Result<(), String> {
+        match key {
+            "at" => self.at = Some(value.number(key)?),
+            "color" => self.color = Some(value.color(key)?),
+            _ => return Err(format!("unknown stop key '{key}'")),
+        }
+        Ok(())
+    }
+
+    /// Validates the table and builds the `n`th (1-based) palette stop.
+    fn build(self, n: usize) -> Result<(f64, Rgb), SceneFileError> {
+        let missing = |key: &str| {
+            SceneFileError::at(self.line, format!("stop {n}: missing required key '{key}'"))
+        };
+        Ok((self.at.ok_or_else(|| missing("at"))?, self.color.ok_or_else(|| missing("color"))?))
+    }
+}
+
+enum Value {
+    Number(f64),
+    String(String),
+    Bool(bool),
+}
+
+impl Value {
+    fn parse(text: &str) -> Option<Self> {
+        if let Some(inner) = text.strip_prefix('"') {
+            return inner
+                .strip_suffix('"')
+                .filter(|s| !s.contains('"'))
+                .map(|s| Value::String(s.to_string()));
+        }
+        match text {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            _ => text
+                .replace('_', "")
+                .parse()
+                .ok()
+                .filter(|n: &f64| n.is_finite())
+                .map(Value::Number),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "a number",
+            Value::String(_) => "a string",
+            Value::Bool(_) => "a boolean",
+        }
+    }
+
+    fn number(self, key: &str) -> Result<f64, String> {
+        match self {
+            Value::Number(n) => Ok(n),
+            other => Err(format!("'{key}' must be a number, found {}", other.kind())),
+        }
+    }
+
+    fn positive(self, key: &str) -> Result<f64, String> {
+        match self.number(key)? {
+            n if n > 0.0 => Ok(n),
+            _ => Err(format!("'{key}' must be greater than zero")),
+        }
+    }
+
+    fn non_negative(self, key: &str) -> Result<f64, String> {
+        match self.number(key)? {
+            n if n >= 0.0 => Ok(n),
+            _ => Err(format!("'{key}' must not be negative")),
+        }
+    }
+
+    fn string(self, key: &str) -> Result<String, String> {
+        match self {
+            Value::String(s) => Ok(s),
+            other => Err(format!("'{key}' must be a string, found {}", other.kind())),
+        }
+    }
+
+    fn color(self, key: &str) -> Result<Rgb, String> {
+        let hex = self.string(key)?;
+        Rgb::from_hex(&hex).ok_or_else(|| format!("'{key}' must be a \"#rrggbb\" colour, found '{hex}'"))
+    }
+
+    fn falloff(self, key: &str) -> Result<Falloff, String> {
+        let name = self.string(key)?;
+        Falloff::from_name(&name).ok_or_else(|| {
+            let names: Vec<&str> = Falloff::ALL.iter().map(|f| f.name()).collect();
+            format!("unknown falloff '{name}', expected one of: {}", names.join(", "))
+        })
+    }
+
+    fn boolean(self, key: &str) -> Result<bool, String> {
+        match self {
+            Value::Bool(b) => Ok(b),
+            other => Err(format!("'{key}' must be true or false, found {}", other.kind())),
+        }
+    }
+}
+
+/// Drops a trailing `#` comment, ignoring `#` inside strings.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(src: &str) -> String {
+        SceneFile::parse(src).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn parses_scene_and_blob_keys() {
+        let file = SceneFile::parse(
+            "mode = \"gooey\"\nfalloff = \"compact\"\ncycle = false\n\n[[blob]]\nradius = 4.0\nsign = -1\nspeed = 1.5\n",
+        )
+        .unwrap();
+        assert_eq!(file.mode, RenderMode::Gooey);
+        assert_eq!(file.falloff, Falloff::Compact);
+        assert_eq!(file.cycle_seconds, None);
+        assert_eq!(file.blobs.len(), 1);
+        assert_eq!(file.blobs[0].radius, 4.0);
+        assert_eq!(file.blobs[0].sign, -1.0);
+        assert_eq!(file.blobs[0].orbit.unwrap().speed_x, 1.5);
+    }
+
+    #[test]
+    fn hash_inside_string_is_not_a_comment() {
+        let file = SceneFile::parse("[[blob]]\nradius = 1 # size\ncolor = \"#ff8000\" # orange\n").unwrap();
+        assert_eq!(file.blobs[0].color, Some(Rgb::new(255, 128, 0)));
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        assert_eq!(
+            error("[[blob]]\nradius = 1\nradius = 2\n"),
+            "line 3: blob 1: duplicate key 'radius'"
+        );
+        assert_eq!(
+            error("threshold = 1\nthreshold = 2\n[[blob]]\nradius = 1\n"),
+            "line 2: duplicate key 'threshold'"
+        );
+    }
+
+    #[test]
+    fn requires_blobs_with_a_radius() {
+        assert_eq!(error("mode = \"solid\"\n"), "scene must define at least one [[blob]]");
+        assert_eq!(
+            error("[[blob]]\nradius = 1\n\n[[blob]]\nspeed = 1\n"),
+            "line 4: blob 2: missing required key 'radius'"
+        );
+    }
+
+    #[test]
+    fn reports_bad_values_with_their_line() {
+        assert_eq!(error("[[blob]]\nradius = -1\n"), "line 2: blob 1: 'radius' must be greater than zero");
+        assert_eq!(error("[[blob]]\nradius = \"big\"\n"), "line 2: blob 1: 'radius' must be a number, found a string");
+        assert_eq!(error("[[blob]]\nradius = \"big\n"), "line 2: invalid value for 'radius'");
+        assert_eq!(error("[[blob]]\nradius\n"), "line 2: expected `key = value`");
+        assert_eq!(error("[[blobs]]\n"), "line 1: unknown table [[blobs]]");
+        assert_eq!(error("colour = \"field\"\n"), "line 1: unknown scene key 'colour'");
+    }
+
+    #[test]
+    fn builds_custom_palettes_from_stops() {
+        let file = SceneFile::parse(
+            "[[blob]]\nradius = 1\n\n[[stop]]\nat = 0\ncolor = \"#000000\"\n\n[[stop]]\nat = 1\ncolor = \"#ffffff\"\n",
+        )
+        .unwrap();
+        assert_eq!(file.palette.name(), "custom");
+        assert_eq!(file.palette.sample(0.5), Rgb::new(128, 128, 128));
+
+        assert_eq!(
+            error("[[blob]]\nradius = 1\n[[stop]]\nat = 0\ncolor = \"#000000\"\n"),
+            "line 3: a palette needs at least two stops"
+        );
+        assert_eq!(error("[[stop]]\nat = 0\n[[blob]]\nradius = 1\n"), "line 1: stop 1: missing required key 'color'");
+    }
+
+    #[test]
+    fn rejects_stops_alongside_a_palette() {
+        let src = "palette = \"fire\"\n[[blob]]\nradius = 1\n[[stop]]\nat = 0\ncolor = \"#000000\"\n[[stop]]\nat = 1\ncolor = \"#ffffff\"\n";
+        assert_eq!(error(src), "line 4: use either 'palette' or [[stop]] tables, not both");
+    }
+
+    #[test]
+    fn checks_shape_keys_against_the_shape() {
+        assert_eq!(
+            error("[[blob]]\nradius = 1\nhalf_length = 2\n"),
+            "line 3: blob 1: 'half_length' does not apply to circle shapes"
+        );
+        assert_eq!(
+            error("[[blob]]\nradius = 1\nshape = \"capsule\"\nstretch_x = 2\n"),
+            "line 4: blob 1: 'stretch_x' does not apply to capsule shapes"
+        );
+        assert_eq!(
+            error("[[blob]]\nradius = 1\nshape = \"rect\"\nhalf_width = 2\n"),
+            "line 3: blob 1: rect shapes require 'half_height'"
+        );
+        let file = SceneFile::parse("[[blob]]\nradius = 1\nshape = \"capsule\"\nhalf_length = 2\n").unwrap();
+        assert!(matches!(file.blobs[0].shape, Shape::Capsule(Capsule { half_length, .. }) if half_length == 2.0));
+    }
+
+    #[test]
+    fn parses_physics_table() {
+        let file = SceneFile::parse("[[blob]]\nradius = 1\nmass = 2\n[physics]\ngravity = -5\n").unwrap();
+        let physics = file.physics.unwrap();
+        assert_eq!(physics.gravity, -5.0);
+        assert_eq!(physics.damping, Physics::default().damping);
+        assert_eq!(file.blobs[0].mass, 2.0);
+
+        assert_eq!(error("[physics]\n[physics]\n"), "line 2: duplicate table [physics]");
+        assert_eq!(
+            error("[physics]\nrestitution = 2\n"),
+            "line 2: physics: 'restitution' must be between 0 and 1"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+/// Geometry a blob's field radiates from.
+///
+/// Offsets are relative to the blob centre and already corrected for
+/// [`ASPECT_RATIO`](crate::ASPECT_RATIO), so lengths and angles are as they
+/// appear on screen, in the same units as the blob radius.
+pub trait FieldSource {
+    /// Squared distance from the point at offset `(dx, dy)` to the source.
+    /// The blob's falloff kernel is evaluated at this distance, so its
+    /// radius becomes a thickness wrapped around the shape.
+    fn dist_sq(&self, dx: f64, dy: f64) -> f64;
+
+    /// Gradient of [`dist_sq`](Self::dist_sq) with respect to `dx` and `dy`.
+    fn gradient(&self, dx: f64, dy: f64) -> (f64, f64);
+}
+
+/// Rotates `(dx, dy)` by `-angle`, into the frame of a shape turned by `angle`.
+fn to_local(dx: f64, dy: f64, angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    (dx * cos + dy * sin, -dx * sin + dy * cos)
+}
+
+/// Rotates a local-frame vector by `angle`, back into screen space.
+fn to_world(lx: f64, ly: f64, angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    (lx * cos - ly * sin, lx * sin + ly * cos)
+}
+
+/// A circle stretched along its own axes and turned by `angle` radians.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ellipse {
+    pub stretch_x: f64,
+    pub stretch_y: f64,
+    pub angle: f64,
+}
+
+impl FieldSource for Ellipse {
+    fn dist_sq(&self, dx: f64, dy: f64) -> f64 {
+        let (lx, ly) = to_local(dx, dy, self.angle);
+        let (ex, ey) = (lx / self.stretch_x, ly / self.stretch_y);
+        ex * ex + ey * ey
+    }
+
+    fn gradient(&self, dx: f64, dy: f64) -> (f64, f64) {
+        let (lx, ly) = to_local(dx, dy, self.angle);
+        let gx = 2.0 * lx / (self.stretch_x * self.stretch_x);
+        let gy = 2.0 * ly / (self.stretch_y * self.stretch_y);
+        to_world(gx, gy, self.angle)
+    }
+}
+
+/// A line segment `2 * half_length` long, turned by `angle` radians; with
+/// the blob radius around it this is a capsule.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capsule {
+    pub half_length: f64,
+    pub angle: f64,
+}
+
+impl FieldSource for Capsule {
+    fn dist_sq(&self, dx: f64, dy: f64) -> f64 {
+        let (lx, ly) = to_local(dx, dy, self.angle);
+        let along = lx.abs() - self.half_length;
+        let ox = along.max(0.0);
+        ox * ox + ly * ly
+    }
+
+    fn gradient(&self, dx: f64, dy: f64) -> (f64, f64) {
+        let (lx, ly) = to_local(dx, dy, self.angle);
+        let ox = (lx.abs() - self.half_length).max(0.0);
+        to_world(2.0 * ox * lx.signum(), 2.0 * ly, self.angle)
+    }
+}
+
+/// A rectangle turned by `angle` radians; the blob radius rounds its corners.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RoundedRect {
+    pub half_width: f64,
+    pub half_height: f64,
+    pub angle: f64,
+}
+
+impl FieldSource for RoundedRect {
+    fn dist_sq(&self, dx: f64, dy: f64) -> f64 {
+        let (lx, ly) = to_local(dx, dy, self.angle);
+        let ox = (lx.abs() - self.half_width).max(0.0);
+        let oy = (ly.abs() - self.half_height).max(0.0);
+        ox * ox + oy * oy
+    }
+
+    fn gradient(&self, dx: f64, dy: f64) -> (f64, f64) {
+        let (lx, ly) = to_local(dx, dy, self.angle);
+        let ox = (lx.abs() - self.half_width).max(0.0);
+        let oy = (ly.abs() - self.half_height).max(0.0);
+        to_world(2.0 * ox * lx.signum(), 2.0 * oy * ly.signum(), self.angle)
+    }
+}
+
+/// The primitive a [`Blob`](crate::Blob) is built on.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Shape {
+    /// A point, giving the classic round metaball.
+    #[default]
+    Circle,
+    Ellipse(Ellipse),
+    Capsule(Capsule),
+    RoundedRect(RoundedRect),
+}
+
+impl FieldSource for Shape {
+    fn dist_sq(&self, dx: f64, dy: f64) -> f64 {
+        match self {
+            Shape::Circle => dx * dx + dy * dy,
+            Shape::Ellipse(ellipse) => ellipse.dist_sq(dx, dy),
+            Shape::Capsule(capsule) => capsule.dist_sq(dx, dy),
+            Shape::RoundedRect(rect) => rect.dist_sq(dx, dy),
+        }
+    }
+
+    fn gradient(&self, dx: f64, dy: f64) -> (f64, f64) {
+        match self {
+            Shape::Circle => (2.0 * dx, 2.0 * dy),
+            Shape::Ellipse(ellipse) => ellipse.gradient(dx, dy),
+            Shape::Capsule(capsule) => capsule.gradient(dx, dy),
+            Shape::RoundedRect(rect) => rect.gradient(dx, dy),
+        }
+    }
+}
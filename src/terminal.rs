@@ -0,0 +1,152 @@
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+
+static RESIZED: AtomicBool = AtomicBool::new(false);
+static QUIT_SIGNAL: AtomicI32 = AtomicI32::new(0);
+
+extern "C" fn on_sigwinch(_: libc::c_int) {
+    RESIZED.store(true, Ordering::Relaxed);
+}
+
+extern "C" fn on_quit(signal: libc::c_int) {
+    QUIT_SIGNAL.store(signal, Ordering::Relaxed);
+}
+
+fn install(signal: libc::c_int, handler: extern "C" fn(libc::c_int)) {
+    unsafe {
+        libc::signal(signal, handler as libc::sighandler_t);
+    }
+}
+
+/// Installs a SIGWINCH handler so [`take_resize`] can report size changes.
+pub fn watch_resize() {
+    install(libc::SIGWINCH, on_sigwinch);
+}
+
+/// Returns true once for every batch of SIGWINCH signals received.
+pub fn take_resize() -> bool {
+    RESIZED.swap(false, Ordering::Relaxed)
+}
+
+/// Catches SIGINT, SIGTERM and SIGHUP so the main loop can exit through
+/// [`Screen`]'s destructor instead of being killed mid-frame.
+pub fn watch_quit() {
+    for signal in [libc::SIGINT, libc::SIGTERM, libc::SIGHUP] {
+        install(signal, on_quit);
+    }
+}
+
+/// The first termination signal received, if any.
+pub fn quit_signal() -> Option<i32> {
+    match QUIT_SIGNAL.load(Ordering::Relaxed) {
+        0 => None,
+        signal => Some(signal),
+    }
+}
+
+/// Current terminal size as `(columns, rows)`, or `None` if stdout is not a
+/// terminal.
+pub fn size() -> Option<(usize, usize)> {
+    let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+    let ok = unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut ws) } == 0;
+    if ok && ws.ws_col > 0 && ws.ws_row > 0 {
+        Some((ws.ws_col as usize, ws.ws_row as usize))
+    } else {
+        None
+    }
+}
+
+/// Puts stdin into raw mode: no line buffering, no echo, and reads that
+/// return immediately. Signal keys like Ctrl+C are left enabled so they
+/// still reach [`watch_quit`]'s handler.
+fn enable_raw_mode() -> Option<libc::termios> {
+    let mut original: libc::termios = unsafe { std::mem::zeroed() };
+    if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+        return None;
+    }
+
+    let mut raw = original;
+    raw.c_lflag &= !(libc::ICANON | libc::ECHO);
+    raw.c_cc[libc::VMIN] = 0;
+    raw.c_cc[libc::VTIME] = 0;
+    if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &raw) } != 0 {
+        return None;
+    }
+    Some(original)
+}
+
+/// Prepares the screen for drawing and restores it when dropped, including
+/// on panic.
+pub struct Screen {
+    alternate: bool,
+    termios: Option<libc::termios>,
+}
+
+impl Screen {
+    /// Enables raw mode on stdin, hides the cursor and clears the screen,
+    /// switching to the alternate screen buffer first if `alternate` is set.
+    pub fn enter(alternate: bool) -> io::Result<Self> {
+        let termios = enable_raw_mode();
+
+        let mut stdout = io::stdout();
+        if alternate {
+            write!(stdout, "\x1B[?1049h")?;
+        }
+        write!(stdout, "\x1B[?25l")?; // Hide cursor
+        write!(stdout, "\x1B[2J")?;   // Clear screen
+        stdout.flush()?;
+        Ok(Self { alternate, termios })
+    }
+
+    /// Reads every key pressed since the last call without blocking. Always
+    /// empty if stdin isn't a terminal.
+    ///
+    /// Escape sequences (arrow keys, function keys) are dropped so their bytes
+    /// aren't mistaken for ordinary key presses.
+    pub fn read_keys(&self) -> Vec<char> {
+        if self.termios.is_none() {
+            return Vec::new();
+        }
+
+        let mut buf = [0u8; 64];
+        let n = io::stdin().read(&mut buf).unwrap_or(0);
+        let input = String::from_utf8_lossy(&buf[..n]);
+
+        let mut keys = Vec::new();
+        let mut chars = input.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '\x1B' {
+                keys.push(ch);
+                continue;
+            }
+            // CSI / SS3 sequences end with a byte in '@'..='~'
+            if let Some('[' | 'O') = chars.next() {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+        }
+        keys
+    }
+}
+
+impl Drop for Screen {
+    fn drop(&mut self) {
+        if let Some(termios) = &self.termios {
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, termios);
+            }
+        }
+
+        let mut stdout = io::stdout();
+        let _ = write!(stdout, "\x1B[0m\x1B[?25h"); // Reset attributes, show cursor
+        if self.alternate {
+            let _ = write!(stdout, "\x1B[?1049l");
+        } else {
+            let _ = writeln!(stdout);
+        }
+        let _ = stdout.flush();
+    }
+}
@@ -0,0 +1,294 @@
+//! Display width of characters in a terminal.
+//!
+//! A cut-down version of the Unicode East Asian Width and emoji presentation
+//! tables, enough to tell whether a glyph will take one cell, two, or none.
+
+use std::env;
+
+/// How the terminal draws East Asian Ambiguous characters, such as `○`, `█`
+/// and the box-drawing lines, which are narrow in most Western fonts but
+/// wide in CJK ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AmbiguousWidth {
+    #[default]
+    Narrow,
+    Wide,
+}
+
+impl AmbiguousWidth {
+    pub const ALL: [AmbiguousWidth; 2] = [AmbiguousWidth::Narrow, AmbiguousWidth::Wide];
+
+    /// Guesses from the locale: Chinese, Japanese and Korean locales
+    /// usually draw ambiguous characters wide.
+    pub fn detect() -> Self {
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+        if ["ja", "ko", "zh"].iter().any(|lang| locale.starts_with(lang)) {
+            AmbiguousWidth::Wide
+        } else {
+            AmbiguousWidth::Narrow
+        }
+    }
+
+    /// Looks up a width by its [`name`](Self::name), ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|width| width.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AmbiguousWidth::Narrow => "Narrow",
+            AmbiguousWidth::Wide => "Wide",
+        }
+    }
+}
+
+/// Ranges of characters drawn two cells wide: CJK, Hangul, fullwidth forms
+/// and emoji that default to emoji presentation.
+const WIDE: &[(u32, u32)] = &[
+    (0x1100, 0x115F),
+    (0x231A, 0x231B),
+    (0x2329, 0x232A),
+    (0x23E9, 0x23EC),
+    (0x23F0, 0x23F0),
+    (0x23F3, 0x23F3),
+    (0x25FD, 0x25FE),
+    (0x2614, 0x2615),
+    (0x2648, 0x2653),
+    (0x267F, 0x267F),
+    (0x2693, 0x2693),
+    (0x26A1, 0x26A1),
+    (0x26AA, 0x26AB),
+    (0x26BD, 0x26BE),
+    (0x26C4, 0x26C5),
+    (0x26CE, 0x26CE),
+    (0x26D4, 0x26D4),
+    (0x26EA, 0x26EA),
+    (0x26F2, 0x26F3),
+    (0x26F5, 0x26F5),
+    (0x26FA, 0x26FA),
+    (0x26FD, 0x26FD),
+    (0x2705, 0x2705),
+    (0x270A, 0x270B),
+    (0x2728, 0x2728),
+    (0x274C, 0x274C),
+    (0x274E, 0x274E),
+    (0x2753, 0x2755),
+    (0x2757, 0x2757),
+    (0x2795, 0x2797),
+    (0x27B0, 0x27B0),
+    (0x27BF, 0x27BF),
+    (0x2B1B, 0x2B1C),
+    (0x2B50, 0x2B50),
+    (0x2B55, 0x2B55),
+    (0x2E80, 0x303E),
+    (0x3041, 0x33FF),
+    (0x3400, 0x4DBF),
+    (0x4E00, 0x9FFF),
+    (0xA000, 0xA4CF),
+    (0xA960, 0xA97F),
+    (0xAC00, 0xD7A3),
+    (0xF900, 0xFAFF),
+    (0xFE10, 0xFE19),
+    (0xFE30, 0xFE6F),
+    (0xFF00, 0xFF60),
+    (0xFFE0, 0xFFE6),
+    (0x1F004, 0x1F004),
+    (0x1F0CF, 0x1F0CF),
+    (0x1F18E, 0x1F18E),
+    (0x1F191, 0x1F19A),
+    (0x1F200, 0x1F251),
+    (0x1F300, 0x1F64F),
+    (0x1F680, 0x1F6FF),
+    (0x1F7E0, 0x1F7EB),
+    (0x1F90C, 0x1F9FF),
+    (0x1FA70, 0x1FAFF),
+    (0x20000, 0x3FFFD),
+];
+
+/// Ranges of characters that take no cell of their own: combining marks,
+/// zero-width spaces and joiners, and variation selectors.
+const ZERO: &[(u32, u32)] = &[
+    (0x0300, 0x036F),
+    (0x0483, 0x0489),
+    (0x0591, 0x05BD),
+    (0x0610, 0x061A),
+    (0x064B, 0x065F),
+    (0x200B, 0x200F),
+    (0x20D0, 0x20FF),
+    (0xFE00, 0xFE0F),
+    (0xFE20, 0xFE2F),
+    (0xE0100, 0xE01EF),
+];
+
+/// Ranges of East Asian Ambiguous characters among the symbols, box drawing
+/// and shapes a renderer is likely to use.
+const AMBIGUOUS: &[(u32, u32)] = &[
+    (0x00A1, 0x00A1),
+    (0x00A4, 0x00A4),
+    (0x00A7, 0x00A8),
+    (0x00AA, 0x00AA),
+    (0x00AD, 0x00AE),
+    (0x00B0, 0x00B4),
+    (0x00B6, 0x00BA),
+    (0x00BC, 0x00BF),
+    (0x00D7, 0x00D7),
+    (0x00F7, 0x00F7),
+    (0x0391, 0x03A9),
+    (0x03B1, 0x03C9),
+    (0x0401, 0x0401),
+    (0x0410, 0x044F),
+    (0x0451, 0x0451),
+    (0x2010, 0x2010),
+    (0x2013, 0x2016),
+    (0x2018, 0x2019),
+    (0x201C, 0x201D),
+    (0x2020, 0x2022),
+    (0x2024, 0x2027),
+    (0x2030, 0x2030),
+    (0x2032, 0x2033),
+    (0x2035, 0x2035),
+    (0x203B, 0x203B),
+    (0x203E, 0x203E),
+    (0x20AC, 0x20AC),
+    (0x2190, 0x2199),
+    (0x21D2, 0x21D2),
+    (0x21D4, 0x21D4),
+    (0x2200, 0x2200),
+    (0x2202, 0x2203),
+    (0x2207, 0x2208),
+    (0x220B, 0x220B),
+    (0x220F, 0x220F),
+    (0x2211, 0x2211),
+    (0x2215, 0x2215),
+    (0x221A, 0x221A),
+    (0x221D, 0x2220),
+    (0x2223, 0x2223),
+    (0x2225, 0x2225),
+    (0x2227, 0x222C),
+    (0x222E, 0x222E),
+    (0x2234, 0x2237),
+    (0x223C, 0x223D),
+    (0x2248, 0x2248),
+    (0x2260, 0x2261),
+    (0x2264, 0x2267),
+    (0x2460, 0x24E9),
+    (0x24EB, 0x254B),
+    (0x2550, 0x2573),
+    (0x2580, 0x258F),
+    (0x2592, 0x2595),
+    (0x25A0, 0x25A1),
+    (0x25A3, 0x25A9),
+    (0x25B2, 0x25B3),
+    (0x25B6, 0x25B7),
+    (0x25BC, 0x25BD),
+    (0x25C0, 0x25C1),
+    (0x25C6, 0x25C8),
+    (0x25CB, 0x25CB),
+    (0x25CE, 0x25D1),
+    (0x25E2, 0x25E5),
+    (0x25EF, 0x25EF),
+    (0x2605, 0x2606),
+    (0x2609, 0x2609),
+    (0x260E, 0x260F),
+    (0x2640, 0x2640),
+    (0x2642, 0x2642),
+    (0x2660, 0x2661),
+    (0x2663, 0x2665),
+    (0x2667, 0x266A),
+    (0x266C, 0x266D),
+    (0x266F, 0x266F),
+    (0x273D, 0x273D),
+    (0x2776, 0x277F),
+    (0xFFFD, 0xFFFD),
+];
+
+fn in_table(table: &[(u32, u32)], c: u32) -> bool {
+    table
+        .binary_search_by(|&(lo, hi)| {
+            if hi < c {
+                std::cmp::Ordering::Less
+            } else if lo > c {
+                std::cmp::Ordering::Greater
+            } else {
+                std::cmp::Ordering::Equal
+            }
+        })
+        .is_ok()
+}
+
+/// Number of terminal cells `ch` occupies, 0, 1 or 2, on a terminal that
+/// draws ambiguous characters `ambiguous`. Control characters count as 0.
+pub fn char_width(ch: char, ambiguous: AmbiguousWidth) -> usize {
+    let c = ch as u32;
+    if c < 0x20 || (0x7F..0xA0).contains(&c) || in_table(ZERO, c) {
+        0
+    } else if in_table(WIDE, c)
+        || (ambiguous == AmbiguousWidth::Wide && in_table(AMBIGUOUS, c))
+    {
+        2
+    } else {
+        1
+    }
+}
+
+/// Whether `ch` is one column wide whatever the terminal's ambiguous width.
+/// Geometric shapes and the miscellaneous symbols are never safe: many
+/// fonts draw them wide, or as emoji, even where Unicode says they're narrow.
+pub fn is_narrow_safe(ch: char) -> bool {
+    char_width(ch, AmbiguousWidth::Wide) == 1 && !matches!(ch as u32, 0x25A0..=0x27BF)
+}
+
+/// An ASCII stand-in for `ch` that keeps roughly its shape or weight, for
+/// glyphs that might not be one column wide.
+pub fn narrow_fallback(ch: char) -> char {
+    match ch {
+        '·' | '∘' | '░' | '╭' | '╮' => '.',
+        '○' | '☆' => 'o',
+        '◯' => 'O',
+        '▒' => ':',
+        '●' | '◉' | '█' | '☻' => '@',
+        '◈' | '▓' => '#',
+        '★' | '✦' | '☀' => '*',
+        '♥' => '&',
+        '▀' => '"',
+        '▄' | '▁' | '▂' | '▃' => '_',
+        '▌' | '▐' | '│' => '|',
+        '─' => '-',
+        '╰' | '╯' => '\'',
+        '╱' => '/',
+        '╲' => '\\',
+        _ if ch.is_ascii() => ch,
+        _ => '#',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_widths() {
+        for ambiguous in AmbiguousWidth::ALL {
+            assert_eq!(char_width('a', ambiguous), 1);
+            assert_eq!(char_width('字', ambiguous), 2);
+            assert_eq!(char_width('\u{301}', ambiguous), 0);
+            assert_eq!(char_width('\u{200B}', ambiguous), 0);
+            assert_eq!(char_width('\0', ambiguous), 0);
+        }
+        assert_eq!(char_width('○', AmbiguousWidth::Narrow), 1);
+        assert_eq!(char_width('○', AmbiguousWidth::Wide), 2);
+    }
+
+    #[test]
+    fn fallbacks_are_narrow_safe() {
+        for ch in ['字', '○', '█', '╭', '★', '\u{1FB00}', 'é'] {
+            assert!(is_narrow_safe(narrow_fallback(ch)), "{ch}");
+        }
+    }
+}